@@ -1,23 +1,83 @@
 use csv::WriterBuilder;
-use dji_log_parser::frame::Frame;
-use dji_log_parser::frame::FrameDetails;
+#[cfg(feature = "experimental-records")]
+use dji_log_parser::frame::FrameVision;
+use dji_log_parser::frame::{
+    Frame, FrameAppJoystick, FrameBattery, FrameCamera, FrameCustom, FrameDetails, FrameGimbal,
+    FrameHome, FrameOSD, FrameRC, FrameRecover,
+};
 use dji_log_parser::record::Record;
 use dji_log_parser::DJILog;
 use serde::Serialize;
 
 use crate::{Cli, Exporter};
 
+/// Row of the CSV file, a `Frame` along with the log details.
+///
+/// Variable length sections do not fit in columns: traffic is left out, and app operations are
+/// joined in a single column. Vision columns are only written with the `experimental-records`
+/// feature.
 #[derive(Serialize)]
 struct FrameWithDetails<'a> {
-    frame: &'a Frame,
+    custom: &'a FrameCustom,
+    osd: &'a FrameOSD,
+    gimbal: &'a FrameGimbal,
+    camera: &'a FrameCamera,
+    rc: &'a FrameRC,
+    battery: &'a FrameBattery,
+    home: &'a FrameHome,
+    recover: &'a FrameRecover,
+    app: FrameAppColumns<'a>,
+    #[cfg(feature = "experimental-records")]
+    vision: &'a FrameVision,
     details: &'a FrameDetails,
 }
 
+#[derive(Serialize)]
+struct FrameAppColumns<'a> {
+    tip: &'a str,
+    warn: &'a str,
+    // Operations as `operation=value`, separated with semicolons
+    operations: String,
+    joystick: &'a FrameAppJoystick,
+}
+
+impl<'a> FrameWithDetails<'a> {
+    fn new(frame: &'a Frame, details: &'a FrameDetails) -> Self {
+        let operations = frame
+            .app
+            .operations
+            .iter()
+            .map(|operation| format!("{}={}", operation.operation, operation.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        FrameWithDetails {
+            custom: &frame.custom,
+            osd: &frame.osd,
+            gimbal: &frame.gimbal,
+            camera: &frame.camera,
+            rc: &frame.rc,
+            battery: &frame.battery,
+            home: &frame.home,
+            recover: &frame.recover,
+            app: FrameAppColumns {
+                tip: &frame.app.tip,
+                warn: &frame.app.warn,
+                operations,
+                joystick: &frame.app.joystick,
+            },
+            #[cfg(feature = "experimental-records")]
+            vision: &frame.vision,
+            details,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct CSVExporter;
 
 impl Exporter for CSVExporter {
-    fn export(&self, parser: &DJILog, _records: &Vec<Record>, frames: &Vec<Frame>, args: &Cli) {
+    fn export(&self, parser: &DJILog, _records: &[Record], frames: &[Frame], args: &Cli) {
         if let Some(csv_path) = &args.csv {
             let mut writer = WriterBuilder::new()
                 .has_headers(false)
@@ -33,10 +93,7 @@ impl Exporter for CSVExporter {
                 }
                 // write frame with details
                 writer
-                    .serialize(FrameWithDetails {
-                        frame: &frame,
-                        details: &details,
-                    })
+                    .serialize(FrameWithDetails::new(frame, &details))
                     .unwrap();
             })
         }
@@ -157,9 +214,34 @@ fn get_headers(frame: &Frame) -> Vec<String> {
         "RECOVER.batterySerial".to_string(), // Serial number of the battery
         "APP.tip".to_string(),           // App tip
         "APP.warn".to_string(),          // App warning
-        "DETAILS.totalTime".to_string(), // Total flight time in seconds
+        "APP.operations".to_string(),    // Buttons pressed by the pilot in the app
+        "APP.isJoystickEnabled".to_string(), // Indicates if the on-screen joysticks are used
+        "APP.joystickRoll".to_string(),  // Right on-screen joystick horizontal position
+        "APP.joystickPitch".to_string(), // Right on-screen joystick vertical position
+        "APP.joystickYaw".to_string(),   // Left on-screen joystick horizontal position
+        "APP.joystickThrottle".to_string(), // Left on-screen joystick vertical position
+    ]);
+
+    #[cfg(feature = "experimental-records")]
+    headers.extend(vec![
+        "VISION.isAvoidanceEnabled".to_string(), // Indicates if obstacle avoidance is enabled
+        "VISION.avoidanceMode".to_string(),      // Obstacle avoidance behavior
+        "VISION.isBraking".to_string(), // Indicates if the drone is braking in front of an obstacle
+        "VISION.isBypassing".to_string(), // Indicates if the drone is bypassing an obstacle
+        "VISION.frontDistance".to_string(), // Distance of the closest obstacle in front in meters
+        "VISION.backDistance".to_string(), // Distance of the closest obstacle behind in meters
+        "VISION.leftDistance".to_string(), // Distance of the closest obstacle on the left in meters
+        "VISION.rightDistance".to_string(), // Distance of the closest obstacle on the right in meters
+        "VISION.upDistance".to_string(),    // Distance of the closest obstacle above in meters
+        "VISION.downDistance".to_string(),  // Distance of the closest obstacle below in meters
+        "VISION.brakeDirection".to_string(), // Direction of the obstacle the drone braked for
+        "VISION.warn".to_string(),          // Vision system warning
+    ]);
+
+    headers.extend(vec![
+        "DETAILS.totalTime".to_string(),     // Total flight time in seconds
         "DETAILS.totalDistance".to_string(), // Total distance flown in meters
-        "DETAILS.maxHeight".to_string(), // Maximum height reached during the flight in meters
+        "DETAILS.maxHeight".to_string(),     // Maximum height reached during the flight in meters
         "DETAILS.maxHorizontalSpeed".to_string(), // Maximum horizontal speed reached during the flight in meters per second
         "DETAILS.maxVerticalSpeed".to_string(), // Maximum vertical speed reached during the flight in meters per second
         "DETAILS.photoNum".to_string(),         // Number of photos taken during the flight
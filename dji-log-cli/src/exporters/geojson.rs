@@ -9,7 +9,7 @@ use crate::{Cli, Exporter};
 pub struct GeoJsonExporter;
 
 impl Exporter for GeoJsonExporter {
-    fn export(&self, parser: &DJILog, _records: &Vec<Record>, frames: &Vec<Frame>, args: &Cli) {
+    fn export(&self, parser: &DJILog, _records: &[Record], frames: &[Frame], args: &Cli) {
         if let Some(geojson_path) = &args.geojson {
             // Create a Value::LineString from all the coords.
             let mut coords = vec![];
@@ -24,7 +24,7 @@ struct ExifInfo {
 pub struct ImageExporter;
 
 impl Exporter for ImageExporter {
-    fn export(&self, parser: &DJILog, records: &Vec<Record>, frames: &Vec<Frame>, args: &Cli) {
+    fn export(&self, parser: &DJILog, records: &[Record], frames: &[Frame], args: &Cli) {
         // Get fallback GPS point from track in case of no GPS available on startup
         let mut fallback_latitude = 0.0;
         let mut fallback_longitude = 0.0;
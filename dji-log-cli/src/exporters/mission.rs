@@ -0,0 +1,77 @@
+use dji_log_parser::frame::Frame;
+use dji_log_parser::record::Record;
+use dji_log_parser::{DJILog, Mission};
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, JsonObject, JsonValue, Value};
+use std::{fs::File, io::Write};
+
+use crate::{Cli, Exporter};
+
+pub struct MissionExporter;
+
+impl Exporter for MissionExporter {
+    fn export(&self, _parser: &DJILog, records: &[Record], _frames: &[Frame], args: &Cli) {
+        if let Some(mission_path) = &args.mission {
+            let Some(mission) = Mission::from_records(records) else {
+                eprintln!("Warning: no waypoint mission found, {mission_path} was not created");
+                return;
+            };
+
+            // Planned route, followed by one point per waypoint with its settings and actions
+            let route: Vec<Vec<f64>> = mission
+                .waypoints
+                .iter()
+                .map(|waypoint| {
+                    vec![
+                        waypoint.longitude,
+                        waypoint.latitude,
+                        waypoint.altitude as f64,
+                    ]
+                })
+                .collect();
+
+            let mut properties = match serde_json::to_value(&mission.settings) {
+                Ok(JsonValue::Object(properties)) => properties,
+                _ => JsonObject::new(),
+            };
+            properties.insert(
+                "isComplete".to_string(),
+                JsonValue::Bool(mission.is_complete()),
+            );
+
+            let mut features = vec![feature(Value::LineString(route), properties)];
+            for waypoint in &mission.waypoints {
+                let properties = match serde_json::to_value(waypoint) {
+                    Ok(JsonValue::Object(properties)) => properties,
+                    _ => JsonObject::new(),
+                };
+                features.push(feature(
+                    Value::Point(vec![
+                        waypoint.longitude,
+                        waypoint.latitude,
+                        waypoint.altitude as f64,
+                    ]),
+                    properties,
+                ));
+            }
+
+            let geojson = GeoJson::FeatureCollection(FeatureCollection {
+                bbox: None,
+                features,
+                foreign_members: None,
+            });
+            let mut file = File::create(mission_path).expect("Unable to create mission file");
+            file.write_all(geojson.to_string().as_bytes())
+                .expect("Unable to write mission data");
+        }
+    }
+}
+
+fn feature(value: Value, properties: JsonObject) -> Feature {
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(value)),
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
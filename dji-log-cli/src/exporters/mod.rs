@@ -3,9 +3,13 @@ mod geojson;
 mod image;
 mod json;
 mod kml;
+#[cfg(feature = "experimental-records")]
+mod mission;
 
 pub use csv::CSVExporter;
 pub use geojson::GeoJsonExporter;
 pub use image::ImageExporter;
 pub use json::JsonExporter;
 pub use kml::KmlExporter;
+#[cfg(feature = "experimental-records")]
+pub use mission::MissionExporter;
@@ -1,20 +1,36 @@
-use clap::Parser;
-use dji_log_parser::frame::Frame;
+use clap::{Parser, Subcommand};
+use dji_log_parser::frame::{frames_from_records, Frame};
+use dji_log_parser::keychain::{FsKeychainCache, KeychainCache, KeychainFeaturePoint};
 use dji_log_parser::layout::auxiliary::Department;
-use dji_log_parser::record::Record;
-use dji_log_parser::DJILog;
+use dji_log_parser::record::{Record, RecordEnvelope};
+use dji_log_parser::{DJILog, Flight};
+#[cfg(feature = "experimental-records")]
+use exporters::MissionExporter;
 use exporters::{CSVExporter, GeoJsonExporter, ImageExporter, JsonExporter, KmlExporter};
-use std::fs;
+use redact::RedactArgs;
+use std::ops::Range;
+use utils::flight_path;
 
 mod exporters;
+mod redact;
 mod utils;
 
-#[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[derive(Parser, Clone)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub(crate) struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input log file
-    #[arg(value_name = "FILE")]
-    filepath: String,
+    #[arg(value_name = "FILE", required = true)]
+    filepath: Option<String>,
 
     /// Write JSON output to FILE instead of stdout
     #[arg(short, long)]
@@ -40,6 +56,11 @@ pub(crate) struct Cli {
     #[arg(short, long)]
     csv: Option<String>,
 
+    /// Generate GeoJSON file of the planned waypoint mission
+    #[cfg(feature = "experimental-records")]
+    #[arg(long)]
+    mission: Option<String>,
+
     /// DJI keychain Api Key
     #[arg(short, long)]
     api_key: Option<String>,
@@ -55,45 +76,126 @@ pub(crate) struct Cli {
     /// Custom version for keychain request
     #[arg(long)]
     api_custom_version: Option<u16>,
+
+    /// Look up keychains in DIR before requesting the DJI API, and store fetched keychains there
+    #[arg(long, value_name = "DIR")]
+    keychain_cache: Option<String>,
+
+    /// Never request the DJI API, only use keychains from the keychain cache
+    #[arg(long, requires = "keychain_cache")]
+    offline: bool,
+
+    /// Skip undecodable data and resume at the next record instead of stopping
+    #[arg(long)]
+    recover: bool,
+
+    /// Export each flight of the log separately, suffixing output files with the flight number
+    #[arg(long)]
+    split_flights: bool,
+}
+
+#[derive(Subcommand, Clone)]
+enum Command {
+    /// Write an anonymized copy of a log, without location nor serial numbers
+    Redact(RedactArgs),
 }
 
 pub(crate) trait Exporter {
-    fn export(&self, parser: &DJILog, records: &Vec<Record>, frames: &Vec<Frame>, args: &Cli);
+    fn export(&self, parser: &DJILog, records: &[Record], frames: &[Frame], args: &Cli);
 }
 
 fn main() {
     let args = Cli::parse();
 
-    let bytes = fs::read(&args.filepath).expect("Unable to read file");
-    let parser = DJILog::from_bytes(bytes).expect("Unable to parse file");
-
-    let keychains = if parser.version >= 13 {
-        match &args.api_key {
-            Some(api_key) => {
-                let department = args.api_custom_department.map(Department::from);
-                let version = args.api_custom_version;
+    if let Some(Command::Redact(redact_args)) = &args.command {
+        redact::redact(redact_args);
+        return;
+    }
 
-                let req = parser
-                    .keychains_request_with_custom_params(department, version)
-                    .expect("Unable to create keychain request");
+    let filepath = args
+        .filepath
+        .as_deref()
+        .expect("Input log file is required");
+    let parser = DJILog::open(filepath).expect("Unable to parse file");
 
-                let keychains = req.fetch(api_key, None).expect("Unable to fetch keychain");
+    let keychains = fetch_keychains(
+        &parser,
+        args.api_key.as_deref(),
+        args.api_custom_department.map(Department::from),
+        args.api_custom_version,
+        args.keychain_cache.as_deref(),
+        args.offline,
+    );
 
-                Some(keychains)
-            }
-            None => {
-                panic!("API Key is required for version 13 and above");
-            }
-        }
+    let records_iter = parser
+        .records_iter(keychains)
+        .expect("Unable to parse records");
+    let records_iter = if args.recover {
+        records_iter.with_recovery()
     } else {
-        None
+        records_iter
     };
 
-    let records = parser
-        .records(keychains.clone())
-        .expect("Unable to parse records");
+    let mut envelopes_iter = records_iter.envelopes();
+    let envelopes: Vec<RecordEnvelope> = envelopes_iter.by_ref().collect();
+    let report = envelopes_iter.into_report();
 
-    let frames = parser.frames(keychains).expect("Unable to parse frames");
+    if !report.is_complete() {
+        eprintln!(
+            "Warning: only {:.1}% of the records section was decoded",
+            report.parsed_ratio() * 100.0
+        );
+        for failure in &report.failures {
+            eprintln!(
+                "  record type {:?} at offset {}: {}",
+                failure.record_type, failure.offset, failure.error
+            );
+        }
+    }
+
+    for feature_point in report.undecrypted_feature_points() {
+        let stats = &report.decryption[&feature_point];
+        eprintln!(
+            "Warning: {} of {} {:?} records were not decrypted ({} without key, {} with invalid padding)",
+            stats.records - stats.decrypted,
+            stats.records,
+            feature_point,
+            stats.missing_key,
+            stats.padding_errors
+        );
+    }
+
+    // Frames are built from the decoded records, so the log is decoded only once
+    let frames = frames_from_records(&envelopes, parser.details.clone());
+    let records: Vec<Record> = envelopes
+        .into_iter()
+        .map(|envelope| envelope.record)
+        .collect();
+
+    if args.split_flights {
+        // Images and the planned mission belong to the whole log
+        ImageExporter.export(&parser, &records, &frames, &args);
+        #[cfg(feature = "experimental-records")]
+        MissionExporter.export(&parser, &records, &frames, &args);
+
+        let exporters: Vec<Box<dyn Exporter>> = vec![
+            Box::new(JsonExporter),
+            Box::new(GeoJsonExporter),
+            Box::new(KmlExporter),
+            Box::new(CSVExporter),
+        ];
+
+        for flight in Flight::split(&frames) {
+            let flight_args = args.for_flight(flight.index);
+            let flight_records = flight_records(&records, &flight.frames);
+            let flight_frames = &frames[flight.frames.clone()];
+
+            for exporter in &exporters {
+                exporter.export(&parser, flight_records, flight_frames, &flight_args);
+            }
+        }
+        return;
+    }
 
     let exporters: Vec<Box<dyn Exporter>> = vec![
         Box::new(JsonExporter),
@@ -101,9 +203,100 @@ fn main() {
         Box::new(GeoJsonExporter),
         Box::new(KmlExporter),
         Box::new(CSVExporter),
+        #[cfg(feature = "experimental-records")]
+        Box::new(MissionExporter),
     ];
 
     for exporter in exporters {
         exporter.export(&parser, &records, &frames, &args);
     }
 }
+
+impl Cli {
+    /// Returns the arguments used to export a single flight, with output files suffixed
+    /// by the flight number.
+    fn for_flight(&self, index: usize) -> Cli {
+        let suffix = |path: &Option<String>| path.as_deref().map(|path| flight_path(path, index));
+
+        Cli {
+            output: suffix(&self.output),
+            geojson: suffix(&self.geojson),
+            kml: suffix(&self.kml),
+            csv: suffix(&self.csv),
+            ..self.clone()
+        }
+    }
+}
+
+/// Returns the records a range of frames was built from.
+///
+/// A frame is emitted when the next OSD record is found, so each frame holds the records
+/// from its own OSD record up to the next one.
+///
+fn flight_records<'a>(records: &'a [Record], frames: &Range<usize>) -> &'a [Record] {
+    let osd_positions: Vec<usize> = records
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| matches!(record, Record::OSD(_)))
+        .map(|(position, _)| position)
+        .collect();
+
+    let start = match frames.start {
+        0 => 0,
+        start => osd_positions.get(start).copied().unwrap_or(records.len()),
+    };
+    let end = osd_positions
+        .get(frames.end)
+        .copied()
+        .unwrap_or(records.len());
+
+    &records[start..end.max(start)]
+}
+
+/// Fetches the keychains needed to decrypt logs version 13 and above.
+pub(crate) fn fetch_keychains(
+    parser: &DJILog,
+    api_key: Option<&str>,
+    department: Option<Department>,
+    version: Option<u16>,
+    keychain_cache: Option<&str>,
+    offline: bool,
+) -> Option<Vec<Vec<KeychainFeaturePoint>>> {
+    if parser.version < 13 {
+        return None;
+    }
+
+    let (req, report) = parser
+        .keychains_request_with_report(department, version)
+        .expect("Unable to create keychain request");
+    if let Some(failure) = report.failures.first() {
+        eprintln!(
+            "Warning: keychains stored after offset {} are missing: {}",
+            failure.offset, failure.error
+        );
+    }
+    let cache = keychain_cache.map(FsKeychainCache::new);
+
+    // Keychains found in the cache need neither an API key nor a request
+    if let Some(cache) = &cache {
+        let key = req
+            .cache_key()
+            .expect("Unable to compute keychain cache key");
+        if let Some(keychains) = cache.get(&key).expect("Unable to read keychain cache") {
+            return Some(keychains.keychains);
+        }
+    }
+
+    if offline {
+        panic!("Keychains not found in cache, unable to decode records offline");
+    }
+
+    let api_key = api_key.expect("API Key is required for version 13 and above");
+    let keychains = match &cache {
+        Some(cache) => req.fetch_with_cache(api_key, None, cache),
+        None => req.fetch(api_key, None),
+    }
+    .expect("Unable to fetch keychain");
+
+    Some(keychains)
+}
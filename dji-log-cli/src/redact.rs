@@ -0,0 +1,103 @@
+use clap::Args;
+use dji_log_parser::frame::{Frame, FrameDetails};
+use dji_log_parser::{DJILog, Redaction, SerialRedaction};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::fetch_keychains;
+
+#[derive(Args, Clone)]
+pub(crate) struct RedactArgs {
+    /// Input log file
+    #[arg(value_name = "FILE")]
+    filepath: String,
+
+    /// Write the redacted log to FILE
+    #[arg(short, long)]
+    output: String,
+
+    /// Secret the coordinate shift and serial number hashes derive from
+    #[arg(short, long)]
+    secret: String,
+
+    /// Replace serial numbers by a keyed hash instead of blanking them
+    #[arg(long)]
+    hash_serials: bool,
+
+    /// Keep the images embedded in the log
+    #[arg(long)]
+    keep_images: bool,
+
+    /// Write redacted frames as JSON instead of a log file
+    #[arg(long)]
+    json: bool,
+
+    /// DJI keychain Api Key
+    #[arg(short, long)]
+    api_key: Option<String>,
+
+    /// Look up keychains in DIR before requesting the DJI API, and store fetched keychains there
+    #[arg(long, value_name = "DIR")]
+    keychain_cache: Option<String>,
+
+    /// Never request the DJI API, only use keychains from the keychain cache
+    #[arg(long, requires = "keychain_cache")]
+    offline: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct FrameJsonData {
+    version: u8,
+    details: FrameDetails,
+    frames: Vec<Frame>,
+}
+
+pub(crate) fn redact(args: &RedactArgs) {
+    let parser = DJILog::open(&args.filepath).expect("Unable to parse file");
+
+    let keychains = fetch_keychains(
+        &parser,
+        args.api_key.as_deref(),
+        None,
+        None,
+        args.keychain_cache.as_deref(),
+        args.offline,
+    );
+
+    let redaction = Redaction::new(&args.secret)
+        .with_serials(if args.hash_serials {
+            SerialRedaction::Hash
+        } else {
+            SerialRedaction::Blank
+        })
+        .keep_images(args.keep_images);
+
+    let file = File::create(&args.output).expect("Unable to create output file");
+
+    if args.json {
+        let mut details = parser.details.clone();
+        redaction.apply_details(&mut details);
+
+        let mut frames = parser.frames(keychains).expect("Unable to parse frames");
+        frames
+            .iter_mut()
+            .for_each(|frame| redaction.apply_frame(frame));
+
+        let json_data = serde_json::to_string(&FrameJsonData {
+            version: parser.version,
+            details: details.into(),
+            frames,
+        })
+        .unwrap();
+
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(json_data.as_bytes())
+            .expect("Unable to write data");
+    } else {
+        parser
+            .redact(keychains, &redaction, BufWriter::new(file))
+            .expect("Unable to write redacted log");
+    }
+}
@@ -4,3 +4,19 @@ pub(crate) fn decimal_to_dms(decimal: f64) -> (f64, f64, f64) {
     let seconds = (decimal - degrees - minutes / 60.0) * 3600.0;
     (degrees, minutes, seconds)
 }
+
+/// Suffixes a file path with a flight number, before its extension.
+pub(crate) fn flight_path(path: &str, index: usize) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!(
+            "{}_flight{}.{}",
+            stem,
+            index + 1,
+            extension.to_string_lossy()
+        ),
+        None => format!("{}_flight{}", stem, index + 1),
+    };
+    path.with_file_name(file_name).to_string_lossy().to_string()
+}
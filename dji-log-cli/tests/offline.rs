@@ -0,0 +1,132 @@
+//! Keychains read from the `--keychain-cache` directory, without the DJI API.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use dji_log_parser::keychain::{
+    FeaturePoint, FsKeychainCache, KeychainCache, KeychainFeaturePoint, Keychains,
+};
+use dji_log_parser::{DJILog, DJILogWriter};
+
+const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+const IV: &str = "AAECAwQFBgcICQoLDA0ODw==";
+
+fn keychains() -> Vec<Vec<KeychainFeaturePoint>> {
+    vec![vec![KeychainFeaturePoint {
+        feature_point: FeaturePoint::BaseFeature,
+        aes_key: KEY.into(),
+        aes_iv: IV.into(),
+    }]]
+}
+
+/// Version 13 log with a `KeyStorage` record and OSD records encrypted with `keychains`.
+fn write_log(path: &Path) {
+    let mut v6_log = Vec::new();
+    v6_log.extend(106u64.to_le_bytes());
+    v6_log.extend(436u16.to_le_bytes());
+    v6_log.push(6);
+    v6_log.resize(100, 0);
+    v6_log.extend([1u8, 3, 0, 0, 0, 0xFF]);
+    v6_log.resize(v6_log.len() + 436, 0);
+    let details = DJILog::from_bytes(v6_log).unwrap().details;
+
+    let mut osd = vec![0u8; 53];
+    osd[0..8].copy_from_slice(&6.5f64.to_radians().to_le_bytes());
+    osd[8..16].copy_from_slice(&46.5f64.to_radians().to_le_bytes());
+
+    let mut writer =
+        DJILogWriter::new(Cursor::new(Vec::new()), 13, details, Some(keychains())).unwrap();
+    writer.write_record(56, &[1, 0, 4, 0, 1, 2, 3, 4]).unwrap();
+    // Frames are emitted on the next OSD record
+    writer.write_record(1, &osd).unwrap();
+    writer.write_record(1, &osd).unwrap();
+    std::fs::write(path, writer.finish().unwrap().into_inner()).unwrap();
+}
+
+/// Log file and keychain cache directory, with the keychains of the log cached if `cached`.
+fn setup(name: &str, cached: bool) -> (PathBuf, PathBuf) {
+    let directory =
+        std::env::temp_dir().join(format!("dji-log-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let log_path = directory.join("log.txt");
+    write_log(&log_path);
+
+    let cache_directory = directory.join("cache");
+    if cached {
+        let request = DJILog::open(&log_path)
+            .unwrap()
+            .keychains_request()
+            .unwrap();
+        FsKeychainCache::new(&cache_directory)
+            .put(
+                &request.cache_key().unwrap(),
+                &Keychains::new(&request, keychains()),
+            )
+            .unwrap();
+    }
+
+    (log_path, cache_directory)
+}
+
+fn run(args: &[&Path]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_dji-log"));
+    for arg in args {
+        command.arg(arg);
+    }
+    command.output().unwrap()
+}
+
+fn assert_decrypted(output: &Output) {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("\"latitude\":46.5"), "{}", stdout);
+}
+
+#[test]
+fn cached_keychains_are_used_offline() {
+    let (log, cache) = setup("offline-hit", true);
+    assert_decrypted(&run(&[
+        &log,
+        Path::new("--keychain-cache"),
+        &cache,
+        Path::new("--offline"),
+    ]));
+    std::fs::remove_dir_all(log.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn cached_keychains_need_no_api_key() {
+    let (log, cache) = setup("cache-hit", true);
+    assert_decrypted(&run(&[&log, Path::new("--keychain-cache"), &cache]));
+    std::fs::remove_dir_all(log.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn cache_misses_fail_offline() {
+    let (log, cache) = setup("offline-miss", false);
+    let output = run(&[
+        &log,
+        Path::new("--keychain-cache"),
+        &cache,
+        Path::new("--offline"),
+    ]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Keychains not found in cache"));
+    std::fs::remove_dir_all(log.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn cache_misses_require_an_api_key() {
+    let (log, cache) = setup("cache-miss", false);
+    let output = run(&[&log, Path::new("--keychain-cache"), &cache]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("API Key is required"));
+    std::fs::remove_dir_all(log.parent().unwrap()).unwrap();
+}
@@ -1,7 +1,9 @@
-use dji_log_parser::keychain::FeaturePoint;
+use dji_log_parser::frame::Frame;
+use dji_log_parser::keychain::{FeaturePoint, KeychainFeaturePoint};
 use dji_log_parser::layout::auxiliary::Department;
 use dji_log_parser::layout::details::Platform;
 use dji_log_parser::layout::details::ProductType;
+use dji_log_parser::record::Record;
 use dji_log_parser::DJILog;
 use std::sync::Arc;
 use std::u8;
@@ -182,6 +184,12 @@ pub struct RecordWrapper {
   pub data: Vec<u8>,
 }
 
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RecordsAndFramesWrapper {
+  pub records: Vec<RecordWrapper>,
+  pub frames: Vec<FrameWrapper>,
+}
+
 #[derive(Debug, Clone, uniffi::Record)]
 pub struct FrameWrapper {
   // OSD section
@@ -248,6 +256,91 @@ fn u32_to_feature_point(value: u32) -> FeaturePoint {
   }
 }
 
+// Helper function to convert wrapper keychains to the original format
+fn to_original_keychains(
+  keychains: Option<Vec<Vec<KeychainFeaturePointWrapper>>>,
+) -> Option<Vec<Vec<KeychainFeaturePoint>>> {
+  keychains.map(|chains| {
+    chains
+      .into_iter()
+      .map(|chain| {
+        chain
+          .into_iter()
+          .map(|point| KeychainFeaturePoint {
+            feature_point: u32_to_feature_point(point.feature_point),
+            aes_key: point.aes_key,
+            aes_iv: point.aes_iv,
+          })
+          .collect()
+      })
+      .collect()
+  })
+}
+
+impl From<&Record> for RecordWrapper {
+  fn from(record: &Record) -> Self {
+    RecordWrapper {
+      record_type: format!("{:?}", record),
+      timestamp: 0,     // Simplified for interface
+      data: Vec::new(), // Simplified for interface
+    }
+  }
+}
+
+impl From<&Frame> for FrameWrapper {
+  fn from(frame: &Frame) -> Self {
+    // Convert cell voltages to Vec<f32>
+    let cell_voltages = if !frame.battery.cell_voltages.is_empty() {
+      frame.battery.cell_voltages.to_vec()
+    } else {
+      Vec::new()
+    };
+
+    FrameWrapper {
+      // OSD data
+      fly_time: frame.osd.fly_time,
+      latitude: frame.osd.latitude,
+      longitude: frame.osd.longitude,
+      altitude: frame.osd.altitude,
+      height: frame.osd.height,
+      x_speed: frame.osd.x_speed,
+      y_speed: frame.osd.y_speed,
+      z_speed: frame.osd.z_speed,
+      pitch: frame.osd.pitch,
+      roll: frame.osd.roll,
+      yaw: frame.osd.yaw,
+      gps_num: frame.osd.gps_num,
+
+      // Gimbal data
+      gimbal_pitch: frame.gimbal.pitch,
+      gimbal_roll: frame.gimbal.roll,
+      gimbal_yaw: frame.gimbal.yaw,
+
+      // Camera data
+      is_recording: frame.camera.is_video,
+      is_taking_photo: frame.camera.is_photo,
+
+      // RC data
+      aileron: frame.rc.aileron as u16,
+      elevator: frame.rc.elevator as u16,
+      throttle: frame.rc.throttle as u16,
+      rudder: frame.rc.rudder as u16,
+
+      // Battery data
+      battery_percent: frame.battery.charge_level,
+      battery_voltage: frame.battery.voltage,
+      battery_current: frame.battery.current,
+      battery_temperature: frame.battery.temperature,
+      cell_voltages,
+
+      // Home data
+      home_latitude: frame.home.latitude,
+      home_longitude: frame.home.longitude,
+      home_altitude: frame.home.altitude,
+    }
+  }
+}
+
 /// A wrapper around the DJI log parser for Kotlin bindings
 #[derive(uniffi::Object)]
 pub struct DJILogWrapper {
@@ -267,6 +360,17 @@ impl DJILogWrapper {
       .map_err(|_| DJIError::ParseError)
   }
 
+  /// Constructs a `DJILog` from a file path.
+  ///
+  /// Records are read from the file on demand, so the log is never copied
+  /// across the FFI boundary nor fully loaded in memory.
+  #[uniffi::constructor]
+  pub fn open(path: String) -> Result<Arc<Self>, DJIError> {
+    DJILog::open(path)
+      .map(|log| Arc::new(Self { inner: log }))
+      .map_err(|_| DJIError::ParseError)
+  }
+
   /// Get the log format version
   pub fn version(&self) -> u8 {
     self.inner.version
@@ -372,45 +476,11 @@ impl DJILogWrapper {
     &self,
     keychains: Option<Vec<Vec<KeychainFeaturePointWrapper>>>,
   ) -> Result<Vec<RecordWrapper>, DJIError> {
-    // Convert wrapper keychains to the original format if provided
-    let original_keychains = match keychains {
-      Some(chains) => {
-        let mut result = Vec::new();
-        for chain in chains {
-          let mut original_chain = Vec::new();
-          for point in chain {
-            // Convert u32 to FeaturePoint using helper function
-            let feature_point = u32_to_feature_point(point.feature_point);
-            original_chain.push(dji_log_parser::keychain::KeychainFeaturePoint {
-              feature_point,
-              aes_key: point.aes_key,
-              aes_iv: point.aes_iv,
-            });
-          }
-          result.push(original_chain);
-        }
-        Some(result)
-      }
-      None => None,
-    };
-
     self
       .inner
-      .records(original_keychains)
+      .records(to_original_keychains(keychains))
       .map_err(|_| DJIError::RecordError)
-      .map(|records| {
-        records
-          .into_iter()
-          .map(|record| {
-            let record_type = format!("{:?}", record);
-            RecordWrapper {
-              record_type,
-              timestamp: 0,     // Simplified for interface
-              data: Vec::new(), // Simplified for interface
-            }
-          })
-          .collect()
-      })
+      .map(|records| records.iter().map(RecordWrapper::from).collect())
   }
 
   /// Retrieves the normalized frames from the DJI log
@@ -418,87 +488,25 @@ impl DJILogWrapper {
     &self,
     keychains: Option<Vec<Vec<KeychainFeaturePointWrapper>>>,
   ) -> Result<Vec<FrameWrapper>, DJIError> {
-    // Convert wrapper keychains to the original format if provided
-    let original_keychains = match keychains {
-      Some(chains) => {
-        let mut result = Vec::new();
-        for chain in chains {
-          let mut original_chain = Vec::new();
-          for point in chain {
-            // Convert u32 to FeaturePoint using helper function
-            let feature_point = u32_to_feature_point(point.feature_point);
-            original_chain.push(dji_log_parser::keychain::KeychainFeaturePoint {
-              feature_point,
-              aes_key: point.aes_key,
-              aes_iv: point.aes_iv,
-            });
-          }
-          result.push(original_chain);
-        }
-        Some(result)
-      }
-      None => None,
-    };
-
     self
       .inner
-      .frames(original_keychains)
+      .frames(to_original_keychains(keychains))
       .map_err(|_| DJIError::FrameError)
-      .map(|frames| {
-        frames
-          .into_iter()
-          .map(|frame| {
-            // Convert cell voltages to Vec<f32>
-            let cell_voltages = if !frame.battery.cell_voltages.is_empty() {
-              frame.battery.cell_voltages.to_vec()
-            } else {
-              Vec::new()
-            };
-
-            FrameWrapper {
-              // OSD data
-              fly_time: frame.osd.fly_time,
-              latitude: frame.osd.latitude,
-              longitude: frame.osd.longitude,
-              altitude: frame.osd.altitude,
-              height: frame.osd.height,
-              x_speed: frame.osd.x_speed,
-              y_speed: frame.osd.y_speed,
-              z_speed: frame.osd.z_speed,
-              pitch: frame.osd.pitch,
-              roll: frame.osd.roll,
-              yaw: frame.osd.yaw,
-              gps_num: frame.osd.gps_num,
-
-              // Gimbal data
-              gimbal_pitch: frame.gimbal.pitch,
-              gimbal_roll: frame.gimbal.roll,
-              gimbal_yaw: frame.gimbal.yaw,
-
-              // Camera data
-              is_recording: frame.camera.is_video,
-              is_taking_photo: frame.camera.is_photo,
-
-              // RC data
-              aileron: frame.rc.aileron as u16,
-              elevator: frame.rc.elevator as u16,
-              throttle: frame.rc.throttle as u16,
-              rudder: frame.rc.rudder as u16,
-
-              // Battery data
-              battery_percent: frame.battery.charge_level,
-              battery_voltage: frame.battery.voltage,
-              battery_current: frame.battery.current,
-              battery_temperature: frame.battery.temperature,
-              cell_voltages,
-
-              // Home data
-              home_latitude: frame.home.latitude,
-              home_longitude: frame.home.longitude,
-              home_altitude: frame.home.altitude,
-            }
-          })
-          .collect()
+      .map(|frames| frames.iter().map(FrameWrapper::from).collect())
+  }
+
+  /// Retrieves both the raw records and the normalized frames, decoding the log only once
+  pub fn records_and_frames(
+    &self,
+    keychains: Option<Vec<Vec<KeychainFeaturePointWrapper>>>,
+  ) -> Result<RecordsAndFramesWrapper, DJIError> {
+    self
+      .inner
+      .records_and_frames(to_original_keychains(keychains))
+      .map_err(|_| DJIError::RecordError)
+      .map(|(records, frames)| RecordsAndFramesWrapper {
+        records: records.iter().map(RecordWrapper::from).collect(),
+        frames: frames.iter().map(FrameWrapper::from).collect(),
       })
   }
 }
@@ -1,6 +1,7 @@
 use dji_log_parser::keychain::KeychainFeaturePoint;
 use dji_log_parser::layout::auxiliary::Department;
 use dji_log_parser::DJILog;
+use serde::Serialize;
 use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsValue};
 
 #[wasm_bindgen]
@@ -19,6 +20,9 @@ extern "C" {
 
     #[wasm_bindgen(typescript_type = "Frame[]")]
     pub type JSFrames;
+
+    #[wasm_bindgen(typescript_type = "{ records: Record[]; frames: Frame[] }")]
+    pub type JSRecordsAndFrames;
 }
 
 #[wasm_bindgen(js_name = DJILog)]
@@ -198,4 +202,43 @@ impl DJILogWrapper {
             .map(|value| value.unchecked_into())
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
+
+    /// Retrieves both the parsed raw records and the normalized frames from the DJI log.
+    ///
+    /// Records are decoded and decrypted only once, then converted into frames. This is
+    /// preferred over calling `records` and `frames` in turn when both are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If nothing is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    #[wasm_bindgen(js_name = "recordsAndFrames")]
+    pub fn records_and_frames(
+        &self,
+        keychains: Option<JSKeychains>,
+    ) -> Result<JSRecordsAndFrames, JsValue> {
+        #[derive(Serialize)]
+        struct RecordsAndFrames<R, F> {
+            records: R,
+            frames: F,
+        }
+
+        let keychains: Option<Vec<Vec<KeychainFeaturePoint>>> = match keychains {
+            Some(keychains) => {
+                Some(serde_wasm_bindgen::from_value(keychains.unchecked_into()).unwrap())
+            }
+            None => None,
+        };
+
+        let (records, frames) = self
+            .inner
+            .records_and_frames(keychains)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&RecordsAndFrames { records, frames })
+            .map(|value| value.unchecked_into())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
 }
@@ -40,12 +40,12 @@ where
         // Raw
         0..=6 => Box::new(reader),
         // Xor
-        7..=12 => Box::new(XorDecoder::new(reader, record_type)),
+        7..=12 => xor_decoder(reader, record_type),
         // Xor + AES
         _ => {
             let feature_point = FeaturePoint::from_record_type(record_type, version);
             match feature_point {
-                FeaturePoint::PlaintextFeature => Box::new(XorDecoder::new(reader, record_type)),
+                FeaturePoint::PlaintextFeature => xor_decoder(reader, record_type),
                 _ => {
                     let pair = keychain
                         .borrow()
@@ -54,22 +54,31 @@ where
 
                     match pair {
                         Some(value) => {
-                            let aes_reader = AesDecoder::new(
-                                XorDecoder::new(reader, record_type),
-                                &value.0,
-                                &value.1,
-                                size - 2, // firstChar and lastChar are not part of the content
-                            );
-
-                            // Update keychain with next iv
-                            keychain.borrow_mut().insert(
-                                feature_point,
-                                (aes_reader.next_iv.clone(), value.1.clone()),
-                            );
-
-                            Box::new(aes_reader)
+                            // firstChar and lastChar are not part of the content
+                            let aes_reader =
+                                XorDecoder::new(reader, record_type).and_then(|reader| {
+                                    AesDecoder::new(
+                                        reader,
+                                        &value.0,
+                                        &value.1,
+                                        size.saturating_sub(2),
+                                    )
+                                });
+
+                            match aes_reader {
+                                Ok(aes_reader) => {
+                                    // Update keychain with next iv
+                                    keychain.borrow_mut().insert(
+                                        feature_point,
+                                        (aes_reader.next_iv.clone(), value.1.clone()),
+                                    );
+
+                                    Box::new(aes_reader)
+                                }
+                                Err(error) => Box::new(FailedDecoder(Some(error))),
+                            }
                         }
-                        None => Box::new(XorDecoder::new(reader, record_type)),
+                        None => xor_decoder(reader, record_type),
                     }
                 }
             }
@@ -77,6 +86,141 @@ where
     }
 }
 
+/// Constructs a XOR decoding reader, or a reader failing on first use if the record
+/// content cannot be read.
+pub(crate) fn xor_decoder<'a, R>(reader: R, record_type: u8) -> Box<dyn SeekRead + 'a>
+where
+    R: Read + Seek + 'a,
+{
+    match XorDecoder::new(reader, record_type) {
+        Ok(xor_reader) => Box::new(xor_reader),
+        Err(error) => Box::new(FailedDecoder(Some(error))),
+    }
+}
+
+/// Converts a decoding error to an `io::Error`, so it can go through readers and `binrw`
+/// and be converted back to the typed error by `Error::from`.
+fn decoding_error(error: crate::Error) -> Error {
+    Error::new(ErrorKind::InvalidData, error)
+}
+
+/// Advances the keychain past a record that is skipped without being decoded.
+///
+/// For AES encrypted records, the IV of the next record sharing the same feature point is
+/// the last ciphertext block of this one. Only this block is XOR decoded, the record content
+/// itself is neither decrypted nor parsed.
+///
+/// # Arguments
+///
+/// * `reader` - A reader positioned at the start of the record content.
+/// * `record_type` - The type of record to be skipped.
+/// * `version` - The prefix version.
+/// * `keychain` - A reference to the keychain to update.
+/// * `size` - The size of the record content.
+///
+pub fn skip_record<R>(
+    reader: R,
+    record_type: u8,
+    version: u8,
+    keychain: &RefCell<Keychain>,
+    size: u16,
+) -> Result<()>
+where
+    R: Read + Seek,
+{
+    if version < 13 {
+        return Ok(());
+    }
+
+    let feature_point = FeaturePoint::from_record_type(record_type, version);
+    if feature_point == FeaturePoint::PlaintextFeature {
+        return Ok(());
+    }
+
+    let key = match keychain.borrow().get(&feature_point) {
+        Some(value) => value.1.clone(),
+        None => return Ok(()),
+    };
+
+    // firstChar and lastChar are not part of the content
+    let content_size = (size as u64).saturating_sub(2);
+    let block_size = Aes256::block_size() as u64;
+    if content_size < block_size {
+        return Err(decoding_error(crate::Error::ShortCiphertext {
+            length: content_size as usize,
+        }));
+    }
+
+    let mut xor_reader = XorDecoder::new(reader, record_type)?;
+    let start_position = xor_reader.start_position;
+    xor_reader.seek(SeekFrom::Start(start_position + content_size - block_size))?;
+
+    let mut next_iv = vec![0u8; block_size as usize];
+    xor_reader.read_exact(&mut next_iv)?;
+
+    keychain.borrow_mut().insert(feature_point, (next_iv, key));
+
+    Ok(())
+}
+
+/// Reads and decodes the whole content of a record, without parsing it.
+///
+/// The keychain is updated as with `record_decoder`, including when the record cannot be
+/// decrypted, so the IV chain of the following records is kept.
+///
+/// # Arguments
+///
+/// * `reader` - A reader positioned at the start of the record content.
+/// * `record_type` - The type of record to be decoded.
+/// * `version` - The prefix version.
+/// * `keychain` - A reference to the keychain to use and update.
+/// * `size` - The size of the record content.
+///
+/// # Returns
+///
+/// This function returns the plaintext record content, as given to `DJILogWriter::write_record`.
+///
+pub(crate) fn decode_payload<R>(
+    mut reader: R,
+    record_type: u8,
+    version: u8,
+    keychain: &RefCell<Keychain>,
+    size: u16,
+) -> Result<Vec<u8>>
+where
+    R: Read + Seek,
+{
+    if version <= 6 {
+        let mut payload = vec![0u8; size.into()];
+        reader.read_exact(&mut payload)?;
+        return Ok(payload);
+    }
+
+    // firstChar and lastChar are not part of the content
+    let mut payload = vec![0u8; size.saturating_sub(2).into()];
+    XorDecoder::new(reader, record_type)?.read_exact(&mut payload)?;
+
+    let feature_point = FeaturePoint::from_record_type(record_type, version);
+    if version < 13 || feature_point == FeaturePoint::PlaintextFeature {
+        return Ok(payload);
+    }
+
+    let Some((iv, key)) = keychain.borrow().get(&feature_point).cloned() else {
+        return Ok(payload);
+    };
+
+    // Update keychain with next iv
+    let next_iv = next_iv(&payload)?;
+    keychain
+        .borrow_mut()
+        .insert(feature_point, (next_iv, key.clone()));
+
+    let length = aes_decrypt(&mut payload, &iv, &key)?;
+    payload.truncate(length);
+
+    Ok(payload)
+}
+
 /// Xor Encoding is an internal data encoding method used starting v4
 /// It doesn't require any external keychain
 pub struct XorDecoder<R> {
@@ -87,29 +231,36 @@ pub struct XorDecoder<R> {
 }
 
 impl<R: Read + Seek> XorDecoder<R> {
-    pub fn new(mut reader: R, record_type: u8) -> Self {
+    /// Constructs a decoder from a reader positioned at the start of the record content.
+    ///
+    /// Returns an error if the first content byte, from which the key derives, cannot be read.
+    ///
+    pub fn new(mut reader: R, record_type: u8) -> Result<Self> {
         let mut first_byte = [0u8];
-        reader.read_exact(&mut first_byte).unwrap();
+        reader.read_exact(&mut first_byte)?;
         let first_byte = first_byte[0];
 
-        let start_position = reader.stream_position().unwrap();
-
-        let magic: u64 = 0x123456789ABCDEF0;
-        let key = crc64(
-            first_byte.overflowing_add(record_type).0 as u64,
-            &magic.overflowing_mul(first_byte as u64).0.to_le_bytes(),
-        )
-        .to_le_bytes();
+        let start_position = reader.stream_position()?;
 
-        XorDecoder {
+        Ok(XorDecoder {
             reader,
-            key,
+            key: xor_key(first_byte, record_type),
             start_position,
             decode_position: 0,
-        }
+        })
     }
 }
 
+/// Derives the XOR key of a record from its first content byte and its record type.
+pub(crate) fn xor_key(first_byte: u8, record_type: u8) -> [u8; 8] {
+    let magic: u64 = 0x123456789ABCDEF0;
+    crc64(
+        first_byte.overflowing_add(record_type).0 as u64,
+        &magic.overflowing_mul(first_byte as u64).0.to_le_bytes(),
+    )
+    .to_le_bytes()
+}
+
 impl<R: Read> Read for XorDecoder<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
         let bytes_read = self.reader.read(buf)?;
@@ -125,7 +276,10 @@ impl<R: Seek> Seek for XorDecoder<R> {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
         match pos {
             SeekFrom::Start(position) => {
-                self.decode_position = (position - self.start_position) as usize;
+                self.decode_position =
+                    position.checked_sub(self.start_position).ok_or_else(|| {
+                        Error::new(ErrorKind::InvalidInput, "Seek before record content")
+                    })? as usize;
                 self.reader.seek(pos)
             }
             SeekFrom::Current(_) => self.reader.seek(pos),
@@ -134,29 +288,36 @@ impl<R: Seek> Seek for XorDecoder<R> {
     }
 }
 
+/// AES-256-CBC decoder of the content of records of versions 13 and later.
+///
+/// The whole content is decrypted when the decoder is constructed. Reads past the
+/// plaintext yield zeros.
+///
 pub struct AesDecoder {
     buffer: Cursor<Vec<u8>>,
     pub next_iv: Vec<u8>,
 }
 
 impl AesDecoder {
-    pub fn new<R: Read>(mut reader: R, iv: &[u8], key: &[u8], size: u16) -> AesDecoder {
+    /// Reads and decrypts `size` bytes of ciphertext.
+    ///
+    /// Returns an error wrapping `Error::ShortCiphertext`, `Error::InvalidKeyLength` or
+    /// `Error::InvalidPadding` if the content cannot be decrypted.
+    ///
+    pub fn new<R: Read>(mut reader: R, iv: &[u8], key: &[u8], size: u16) -> Result<AesDecoder> {
         let mut buffer = vec![0u8; size.into()];
-        reader.read_exact(&mut buffer).unwrap();
+        reader.read_exact(&mut buffer)?;
 
         // Get next from last block
-        let next_iv = buffer[buffer.len() - Aes256::block_size()..].to_vec();
+        let next_iv = next_iv(&buffer)?;
 
-        let dec: cbc::Decryptor<Aes256> = Aes256CbcDec::new_from_slices(key, iv).unwrap();
-        let plaintext = dec
-            .decrypt_padded_mut::<Pkcs7>(&mut buffer)
-            .unwrap_or_default()
-            .to_vec();
+        let length = aes_decrypt(&mut buffer, iv, key)?;
+        buffer.truncate(length);
 
-        AesDecoder {
-            buffer: Cursor::new(plaintext.to_vec()),
+        Ok(AesDecoder {
+            buffer: Cursor::new(buffer),
             next_iv,
-        }
+        })
     }
 }
 
@@ -172,3 +333,54 @@ impl Seek for AesDecoder {
         self.buffer.seek(pos)
     }
 }
+
+/// Returns the last ciphertext block, used as IV by the next record of the same feature point.
+fn next_iv(ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let block_size = Aes256::block_size();
+    if ciphertext.len() < block_size {
+        return Err(decoding_error(crate::Error::ShortCiphertext {
+            length: ciphertext.len(),
+        }));
+    }
+
+    Ok(ciphertext[ciphertext.len() - block_size..].to_vec())
+}
+
+/// AES-256-CBC decrypts `buffer` in place and returns the plaintext length.
+fn aes_decrypt(buffer: &mut [u8], iv: &[u8], key: &[u8]) -> Result<usize> {
+    let decryptor = Aes256CbcDec::new_from_slices(key, iv).map_err(|_| {
+        decoding_error(crate::Error::InvalidKeyLength {
+            key: key.len(),
+            iv: iv.len(),
+        })
+    })?;
+
+    decryptor
+        .decrypt_padded_mut::<Pkcs7>(buffer)
+        .map(|plaintext| plaintext.len())
+        .map_err(|_| decoding_error(crate::Error::InvalidPadding))
+}
+
+/// Reader standing for a decoder that could not be constructed. It fails with the
+/// construction error on first use.
+struct FailedDecoder(Option<Error>);
+
+impl FailedDecoder {
+    fn error(&mut self) -> Error {
+        self.0
+            .take()
+            .unwrap_or_else(|| Error::new(ErrorKind::InvalidData, "Undecodable record"))
+    }
+}
+
+impl Read for FailedDecoder {
+    fn read(&mut self, _buf: &mut [u8]) -> Result<usize> {
+        Err(self.error())
+    }
+}
+
+impl Seek for FailedDecoder {
+    fn seek(&mut self, _pos: SeekFrom) -> Result<u64> {
+        Err(self.error())
+    }
+}
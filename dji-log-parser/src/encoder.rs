@@ -0,0 +1,106 @@
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockEncryptMut, BlockSizeUser, KeyIvInit};
+use aes::Aes256;
+use std::cell::RefCell;
+
+use crate::decoder::xor_key;
+use crate::keychain::{FeaturePoint, Keychain};
+use crate::{Error, Result};
+
+type Aes256CbcEnc = cbc::Encryptor<Aes256>;
+
+/// Encodes the content of a record, reversing `decoder::record_decoder`.
+///
+/// # Arguments
+///
+/// * `record_type` - The type of record to be encoded.
+/// * `version` - The prefix version.
+/// * `keychain` - A reference to a keychain for encryption. The IV of the record feature point
+///   is updated the same way it is when decoding.
+/// * `payload` - The plaintext record content.
+///
+/// # Returns
+///
+/// This function returns the encoded record content, to be written between the record
+/// length and the end byte, or an error if the keychain entry is not a valid AES-256 key and IV.
+///
+pub(crate) fn record_encoder(
+    record_type: u8,
+    version: u8,
+    keychain: &RefCell<Keychain>,
+    payload: &[u8],
+) -> Result<Vec<u8>> {
+    match version {
+        // Raw
+        0..=6 => Ok(payload.to_vec()),
+        // Xor
+        7..=12 => Ok(xor_encode(record_type, payload)),
+        // Xor + AES
+        _ => {
+            let feature_point = FeaturePoint::from_record_type(record_type, version);
+            if feature_point == FeaturePoint::PlaintextFeature {
+                return Ok(xor_encode(record_type, payload));
+            }
+
+            let pair = keychain
+                .borrow()
+                .get(&feature_point)
+                .map(|value| (value.0.clone(), value.1.clone()));
+
+            match pair {
+                Some((iv, key)) => {
+                    let ciphertext = aes_encode(payload, &iv, &key)?;
+
+                    // Update keychain with next iv
+                    let next_iv = ciphertext[ciphertext.len() - Aes256::block_size()..].to_vec();
+                    keychain.borrow_mut().insert(feature_point, (next_iv, key));
+
+                    Ok(xor_encode(record_type, &ciphertext))
+                }
+                None => Ok(xor_encode(record_type, payload)),
+            }
+        }
+    }
+}
+
+/// Xor encodes `payload`, framed by the first byte the key derives from and a last byte
+/// which is not part of the content.
+pub(crate) fn xor_encode(record_type: u8, payload: &[u8]) -> Vec<u8> {
+    let first_byte = (payload.len() as u8)
+        .wrapping_mul(31)
+        .wrapping_add(record_type);
+    let key = xor_key(first_byte, record_type);
+
+    let mut content = Vec::with_capacity(payload.len() + 2);
+    content.push(first_byte);
+    content.extend(
+        payload
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| byte ^ key[i % 8]),
+    );
+    content.push(0);
+    content
+}
+
+/// AES-256-CBC encrypts `payload` with PKCS7 padding.
+fn aes_encode(payload: &[u8], iv: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let block_size = Aes256::block_size();
+    let mut buffer = vec![0u8; (payload.len() / block_size + 1) * block_size];
+    buffer[..payload.len()].copy_from_slice(payload);
+
+    let encryptor =
+        Aes256CbcEnc::new_from_slices(key, iv).map_err(|_| Error::InvalidKeyLength {
+            key: key.len(),
+            iv: iv.len(),
+        })?;
+
+    // The buffer always has room for the padding
+    let length = encryptor
+        .encrypt_padded_mut::<Pkcs7>(&mut buffer, payload.len())
+        .map(|ciphertext| ciphertext.len())
+        .unwrap_or(buffer.len());
+    buffer.truncate(length);
+
+    Ok(buffer)
+}
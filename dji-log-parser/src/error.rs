@@ -11,20 +11,62 @@ pub enum Error {
     #[error("DJI Api error: {0}")]
     ApiError(String),
 
+    #[error("DJI Api quota exceeded")]
+    ApiQuotaExceeded,
+
+    #[error("Keychains version not supported by the DJI Api: {0}")]
+    ApiUnsupportedVersion(u16),
+
     #[error("Keychain is required")]
     KeychainRequired,
 
+    #[error("Keychains belong to another log: expected fingerprint {expected}, found {found}")]
+    KeychainMismatch { expected: String, found: String },
+
+    #[error("Unsupported keychains file format: {0}")]
+    UnsupportedKeychainsFormat(u32),
+
+    #[error("Invalid AES keychain: {key} bytes key and {iv} bytes IV, expected 32 and 16")]
+    InvalidKeyLength { key: usize, iv: usize },
+
+    #[error("Encrypted record content too short: {length} bytes, expected at least one AES block")]
+    ShortCiphertext { length: usize },
+
+    #[error("Invalid AES padding, the keychain does not match the log")]
+    InvalidPadding,
+
+    #[error("Record too long for log version {version}: {length} bytes")]
+    RecordTooLong { version: u8, length: usize },
+
+    #[error("Unsupported log version for writing: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Record at offset {0} has no payload to write")]
+    MissingPayload(u64),
+
+    #[error("Unknown record type: {0}")]
+    UnknownRecordType(String),
+
+    #[error("Not a DJI log: {0}")]
+    NotDJILog(String),
+
+    #[error("KeyStorage records could not be read past offset {offset}: {error}")]
+    IncompleteKeyStorage { offset: u64, error: Box<Error> },
+
+    #[error("Records could not be read past offset {offset}: {error}")]
+    IncompleteRecords { offset: u64, error: Box<Error> },
+
     #[error("Missing Auxilliary data: {0}")]
     MissingAuxilliaryData(String),
 
     #[error("Parse error: {0}")]
-    Parse(#[from] binrw::Error),
+    Parse(#[source] binrw::Error),
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
     #[error("Io error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(#[source] std::io::Error),
 
     #[error("Base64 decode error: {0}")]
     Base64Decode(#[from] base64::DecodeError),
@@ -34,4 +76,48 @@ pub enum Error {
 
     #[error("Network connection error")]
     NetworkConnection,
+
+    #[error("Network request timed out")]
+    NetworkTimeout,
+
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+}
+
+impl Error {
+    /// Returns the decoding error carried by an `io::Error`, as produced by the decoders.
+    fn from_decoder(error: &std::io::Error) -> Option<Error> {
+        match error.get_ref()?.downcast_ref::<Error>()? {
+            Error::InvalidKeyLength { key, iv } => {
+                Some(Error::InvalidKeyLength { key: *key, iv: *iv })
+            }
+            Error::ShortCiphertext { length } => Some(Error::ShortCiphertext { length: *length }),
+            Error::InvalidPadding => Some(Error::InvalidPadding),
+            _ => None,
+        }
+    }
+
+    /// Returns the decoding error at the origin of a `binrw` error, if any.
+    fn from_binrw(error: &binrw::Error) -> Option<Error> {
+        match error {
+            binrw::Error::Io(error) => Error::from_decoder(error),
+            binrw::Error::Backtrace(backtrace) => Error::from_binrw(&backtrace.error),
+            binrw::Error::EnumErrors { variant_errors, .. } => variant_errors
+                .iter()
+                .find_map(|(_, error)| Error::from_binrw(error)),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::from_decoder(&error).unwrap_or(Error::Io(error))
+    }
+}
+
+impl From<binrw::Error> for Error {
+    fn from(error: binrw::Error) -> Self {
+        Error::from_binrw(&error).unwrap_or(Error::Parse(error))
+    }
 }
@@ -0,0 +1,90 @@
+use std::collections::BTreeSet;
+
+use crate::record::{JPEG_TYPE_ID, KEY_STORAGE_RECOVER_TYPE_ID, KNOWN_RECORD_TYPES};
+use crate::{Error, Result};
+
+/// Set of record types to decode.
+///
+/// Records whose type is not in the filter are skipped using their length prefix, without
+/// being decrypted nor parsed. Keychain IVs are still advanced for skipped encrypted
+/// records, so the records kept decode exactly as without a filter.
+///
+/// Record types are identified by their type id, i.e. the first byte of the record, or by
+/// the name of their `Record` variant. `JPEG` records use the type id `0xFF`.
+/// `KeyStorageRecover` records are always decoded, as they switch to the next keychain.
+///
+#[derive(Debug, Clone, Default)]
+pub struct RecordFilter {
+    type_ids: BTreeSet<u8>,
+}
+
+impl RecordFilter {
+    /// Creates a filter keeping the given record type ids.
+    pub fn new(type_ids: impl IntoIterator<Item = u8>) -> Self {
+        RecordFilter {
+            type_ids: type_ids.into_iter().collect(),
+        }
+    }
+
+    /// Creates a filter keeping the given `Record` variants, e.g. `["OSD", "Home"]`.
+    ///
+    /// Returns `Error::UnknownRecordType` if a name does not match a `Record` variant
+    /// with a dedicated type id.
+    ///
+    pub fn from_names<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Result<Self> {
+        let type_ids = names
+            .into_iter()
+            .map(|name| {
+                let name = name.as_ref();
+                if name == "JPEG" {
+                    return Ok(JPEG_TYPE_ID);
+                }
+                KNOWN_RECORD_TYPES
+                    .iter()
+                    .find(|(_, known)| known.eq_ignore_ascii_case(name))
+                    .map(|(type_id, _)| *type_id)
+                    .ok_or_else(|| Error::UnknownRecordType(name.to_string()))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(RecordFilter { type_ids })
+    }
+
+    /// Creates a filter keeping only the records used to build frames.
+    pub fn frames() -> Self {
+        Self::from_names([
+            "OSD",
+            "Home",
+            "Gimbal",
+            "RC",
+            "Custom",
+            "CenterBattery",
+            "SmartBattery",
+            "AppTip",
+            "AppWarn",
+            "Recover",
+            "SmartBatteryGroup",
+            "AppSeriousWarn",
+            "AppOperation",
+            "AppSpecialControlJoyStick",
+            "Camera",
+            "OFDM",
+            "RCDisplayField",
+            "ADSBFlightData",
+            "VisionGroup",
+            "VisionWarning",
+        ])
+        .expect("frame record names are known")
+    }
+
+    /// Adds a record type id to the filter.
+    pub fn with(mut self, type_id: u8) -> Self {
+        self.type_ids.insert(type_id);
+        self
+    }
+
+    /// Returns `true` if records of the given type id are decoded.
+    pub fn accepts(&self, type_id: u8) -> bool {
+        type_id == KEY_STORAGE_RECOVER_TYPE_ID || self.type_ids.contains(&type_id)
+    }
+}
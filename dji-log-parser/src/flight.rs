@@ -0,0 +1,195 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::ops::Range;
+
+use crate::frame::Frame;
+
+/// Mean Earth radius in meters, used to compute distances between positions.
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// A single flight, from motor start to motor stop, within a DJI log.
+///
+/// A log can hold several takeoffs and landings. Flights are split from the log frames
+/// using the `is_motor_on` and `is_on_ground` OSD flags, and the `current_flight_record_index`
+/// of Home records. Motor starts without takeoff are not considered flights.
+///
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Flight {
+    /// Index of the flight in the log, starting at 0
+    pub index: usize,
+    /// Flight record index, as found in Home records
+    pub flight_record_index: u16,
+    /// Range of the flight frames, as returned by `DJILog::frames`
+    pub frames: Range<usize>,
+    /// Date and time of the first flight frame
+    pub start_time: DateTime<Utc>,
+    /// Date and time of the last flight frame
+    pub end_time: DateTime<Utc>,
+    /// Takeoff latitude in degrees
+    pub takeoff_latitude: f64,
+    /// Takeoff longitude in degrees
+    pub takeoff_longitude: f64,
+    /// Landing latitude in degrees
+    pub landing_latitude: f64,
+    /// Landing longitude in degrees
+    pub landing_longitude: f64,
+    /// Flight summary statistics
+    pub summary: FlightSummary,
+}
+
+/// Summary statistics of a `Flight`.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightSummary {
+    /// Flight time in seconds
+    pub duration: f32,
+    /// Distance flown in meters
+    pub distance: f32,
+    /// Maximum height above the takeoff point in meters
+    pub max_height: f32,
+    /// Maximum horizontal speed in meters per second
+    pub max_horizontal_speed: f32,
+    /// Maximum vertical speed in meters per second
+    pub max_vertical_speed: f32,
+    /// Maximum distance from the home point in meters
+    pub max_distance_from_home: f32,
+}
+
+impl Flight {
+    /// Splits a sequence of frames into flights.
+    ///
+    /// A flight starts at the first frame with motors on or off the ground, and ends after
+    /// the last one. A change of `current_flight_record_index` during a flight also starts a
+    /// new flight.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - The frames of a log, in log order.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the flights found, in log order. Their frame ranges index `frames`.
+    ///
+    pub fn split(frames: &[Frame]) -> Vec<Flight> {
+        let mut ranges = Vec::new();
+        let mut start: Option<usize> = None;
+
+        for (index, frame) in frames.iter().enumerate() {
+            let in_flight = frame.osd.is_motor_on || !frame.osd.is_on_ground;
+
+            match start {
+                Some(start_index) if !in_flight => {
+                    ranges.push(start_index..index);
+                    start = None;
+                }
+                Some(start_index) => {
+                    let previous_index = frames[index - 1].home.current_flight_record_index;
+                    let flight_index = frame.home.current_flight_record_index;
+                    if previous_index != 0 && flight_index != 0 && previous_index != flight_index {
+                        ranges.push(start_index..index);
+                        start = Some(index);
+                    }
+                }
+                None if in_flight => start = Some(index),
+                None => {}
+            }
+        }
+
+        if let Some(start_index) = start {
+            ranges.push(start_index..frames.len());
+        }
+
+        ranges
+            .into_iter()
+            .filter(|range| {
+                frames[range.clone()]
+                    .iter()
+                    .any(|frame| !frame.osd.is_on_ground)
+            })
+            .enumerate()
+            .map(|(index, range)| Flight::from_frames(index, range, frames))
+            .collect()
+    }
+
+    fn from_frames(index: usize, range: Range<usize>, frames: &[Frame]) -> Flight {
+        let flight_frames = &frames[range.clone()];
+
+        let positions: Vec<(f64, f64)> = flight_frames
+            .iter()
+            .filter(|frame| frame.osd.latitude != 0.0 || frame.osd.longitude != 0.0)
+            .map(|frame| (frame.osd.latitude, frame.osd.longitude))
+            .collect();
+        let airborne_position = |frame: &&Frame| {
+            !frame.osd.is_on_ground && (frame.osd.latitude != 0.0 || frame.osd.longitude != 0.0)
+        };
+
+        let (takeoff_latitude, takeoff_longitude) = flight_frames
+            .iter()
+            .find(airborne_position)
+            .map(|frame| (frame.osd.latitude, frame.osd.longitude))
+            .or(positions.first().copied())
+            .unwrap_or_default();
+        let (landing_latitude, landing_longitude) = flight_frames
+            .iter()
+            .rev()
+            .find(airborne_position)
+            .map(|frame| (frame.osd.latitude, frame.osd.longitude))
+            .or(positions.last().copied())
+            .unwrap_or_default();
+
+        let first = &flight_frames[0];
+        let last = &flight_frames[flight_frames.len() - 1];
+
+        let mut summary = FlightSummary {
+            duration: (last.custom.date_time - first.custom.date_time).num_milliseconds() as f32
+                / 1000.0,
+            distance: positions
+                .windows(2)
+                .map(|pair| distance(pair[0], pair[1]))
+                .sum::<f64>() as f32,
+            ..FlightSummary::default()
+        };
+
+        for frame in flight_frames {
+            let horizontal_speed = frame.osd.x_speed.hypot(frame.osd.y_speed);
+
+            summary.max_height = summary.max_height.max(frame.osd.height);
+            summary.max_horizontal_speed = summary.max_horizontal_speed.max(horizontal_speed);
+            summary.max_vertical_speed = summary.max_vertical_speed.max(frame.osd.z_speed.abs());
+
+            if frame.home.latitude != 0.0 || frame.home.longitude != 0.0 {
+                let distance_from_home = distance(
+                    (frame.home.latitude, frame.home.longitude),
+                    (frame.osd.latitude, frame.osd.longitude),
+                ) as f32;
+                summary.max_distance_from_home =
+                    summary.max_distance_from_home.max(distance_from_home);
+            }
+        }
+
+        Flight {
+            index,
+            flight_record_index: last.home.current_flight_record_index,
+            frames: range,
+            start_time: first.custom.date_time,
+            end_time: last.custom.date_time,
+            takeoff_latitude,
+            takeoff_longitude,
+            landing_latitude,
+            landing_longitude,
+            summary,
+        }
+    }
+}
+
+/// Great circle distance in meters between two positions given in degrees.
+pub(crate) fn distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (latitude1, longitude1) = (from.0.to_radians(), from.1.to_radians());
+    let (latitude2, longitude2) = (to.0.to_radians(), to.1.to_radians());
+
+    let a = ((latitude2 - latitude1) / 2.0).sin().powi(2)
+        + latitude1.cos() * latitude2.cos() * ((longitude2 - longitude1) / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
@@ -0,0 +1,156 @@
+use binrw::BinRead;
+use std::io::{Seek, SeekFrom};
+use std::path::Path;
+
+use crate::frame::{Frame, FrameBuilder};
+use crate::iter::RecordCursor;
+use crate::keychain::KeychainFeaturePoint;
+use crate::layout::prefix::Prefix;
+use crate::record::Record;
+use crate::{DJILog, ParseReport, Result};
+
+/// Records and frames completed since the previous `DJILogFollower::poll`.
+#[derive(Debug, Default)]
+pub struct FollowerUpdate {
+    /// Newly decoded records, in log order
+    pub records: Vec<Record>,
+    /// Newly completed frames, in log order
+    pub frames: Vec<Frame>,
+}
+
+/// Live tail of a DJI log that is still being written.
+///
+/// Each call to `poll` decodes the records appended to the file since the previous call.
+/// A record is only decoded once it is fully written: a partially written record at the
+/// end of the file is held back until the next poll. Frame state is kept between polls,
+/// so frames are built exactly as with `DJILog::frames`.
+///
+/// Undecodable records never stop the follower, it resynchronizes on the next record
+/// header instead, as `RecordIter::with_recovery` does.
+///
+pub struct DJILogFollower {
+    log: DJILog,
+    cursor: RecordCursor,
+    frames: FrameBuilder,
+}
+
+impl DJILogFollower {
+    /// Starts following the DJI log file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the DJI log file.
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13).
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<DJILogFollower>`. Nothing is decoded until `poll` is called.
+    ///
+    pub fn open(
+        path: impl AsRef<Path>,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<DJILogFollower> {
+        Self::from_log(DJILog::open(path)?, keychains)
+    }
+
+    /// Starts following an already constructed `DJILog`.
+    ///
+    /// The log must be backed by a reader that sees data appended to the file, as
+    /// with `DJILog::open`.
+    ///
+    pub fn from_log(
+        log: DJILog,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<DJILogFollower> {
+        let keychains = log.decryption_keychains(keychains)?;
+
+        let mut cursor = RecordCursor::new(&log, keychains);
+        cursor.recovery = true;
+
+        let frames = FrameBuilder::new(log.details.clone());
+
+        Ok(DJILogFollower {
+            log,
+            cursor,
+            frames,
+        })
+    }
+
+    /// Returns the followed log.
+    pub fn log(&self) -> &DJILog {
+        &self.log
+    }
+
+    /// Returns the report of the records decoded so far.
+    pub fn report(&self) -> &ParseReport {
+        &self.cursor.report
+    }
+
+    /// Decodes the records fully written since the previous poll.
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<FollowerUpdate>`. The update is empty when no complete
+    /// record was appended to the file.
+    ///
+    pub fn poll(&mut self) -> Result<FollowerUpdate> {
+        self.refresh()?;
+
+        let mut update = FollowerUpdate::default();
+
+        while self.cursor.position < self.cursor.end_offset
+            && self.cursor.is_record_complete(&self.log)
+        {
+            let Some(envelope) = self.cursor.next_envelope(&self.log) else {
+                break;
+            };
+
+            if let Some(frame) = self.frames.push(&envelope) {
+                update.frames.push(frame);
+            }
+            update.records.push(envelope.record);
+        }
+
+        Ok(update)
+    }
+
+    /// Updates the end of the records section from the current file size.
+    fn refresh(&mut self) -> Result<()> {
+        let (size, prefix) = {
+            let mut reader = self.log.reader();
+            let size = reader.seek(SeekFrom::End(0))?;
+
+            // Before version 12, details are written at the end of the file once the
+            // flight is over, so the prefix is read again to get their offset
+            let prefix = if self.log.version < 12 {
+                reader.rewind()?;
+                Some(Prefix::read(&mut *reader)?)
+            } else {
+                None
+            };
+
+            (size, prefix)
+        };
+
+        if let Some(prefix) = prefix {
+            if prefix.detail_offset() > prefix.records_offset() {
+                self.log.prefix = prefix;
+            }
+        }
+
+        let end_offset = if self.log.version < 12
+            && self.log.prefix.detail_offset() <= self.log.prefix.records_offset()
+        {
+            size
+        } else {
+            self.log.prefix.records_end_offset(size)
+        };
+
+        self.log.size = size;
+        self.cursor.end_offset = end_offset;
+        self.cursor.report.records_end_offset = end_offset;
+
+        Ok(())
+    }
+}
@@ -10,4 +10,34 @@ pub struct FrameApp {
     pub tip: String,
     // App warning
     pub warn: String,
+    // Buttons pressed by the pilot in the app during the frame
+    pub operations: Vec<FrameAppOperation>,
+    // On-screen joysticks of the app, as of the last joystick record
+    pub joystick: FrameAppJoystick,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct FrameAppOperation {
+    /// Operation code of the button pressed by the pilot, as logged
+    pub operation: u8,
+    /// Operation specific value
+    pub value: u8,
+}
+
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct FrameAppJoystick {
+    /// Indicates if the on-screen joysticks are used instead of the remote controller
+    pub is_enabled: bool,
+    /// Right joystick horizontal position, [-1, 1] from left to right
+    pub roll: f32,
+    /// Right joystick vertical position, [-1, 1] from back to front
+    pub pitch: f32,
+    /// Left joystick horizontal position, [-1, 1] from left to right
+    pub yaw: f32,
+    /// Left joystick vertical position, [-1, 1] from down to up
+    pub throttle: f32,
 }
@@ -2,10 +2,14 @@ use serde::Serialize;
 #[cfg(target_arch = "wasm32")]
 use tsify_next::Tsify;
 
+#[cfg(feature = "experimental-records")]
+use crate::flight::distance;
 use crate::layout::details::Details;
 use crate::record::osd::{AppCommand, GroundOrSky};
 use crate::record::smart_battery_group::SmartBatteryGroup;
-use crate::record::Record;
+#[cfg(feature = "experimental-records")]
+use crate::record::vision_group::{VisionDirection, VisionGroup};
+use crate::record::{Record, RecordEnvelope};
 use crate::utils::append_message;
 
 mod app;
@@ -18,8 +22,12 @@ mod home;
 mod osd;
 mod rc;
 mod recover;
+#[cfg(feature = "experimental-records")]
+mod traffic;
+#[cfg(feature = "experimental-records")]
+mod vision;
 
-pub use app::FrameApp;
+pub use app::{FrameApp, FrameAppJoystick, FrameAppOperation};
 pub use battery::FrameBattery;
 pub use camera::FrameCamera;
 pub use custom::FrameCustom;
@@ -29,6 +37,14 @@ pub use home::FrameHome;
 pub use osd::FrameOSD;
 pub use rc::FrameRC;
 pub use recover::FrameRecover;
+#[cfg(feature = "experimental-records")]
+pub use traffic::FrameTraffic;
+#[cfg(feature = "experimental-records")]
+pub use vision::FrameVision;
+
+/// Seconds of flight after which the traffic of the last ADS-B record is dropped.
+#[cfg(feature = "experimental-records")]
+const TRAFFIC_TIMEOUT: f32 = 5.0;
 
 /// Represents a normalized frame of data from a DJI log.
 ///
@@ -49,18 +65,112 @@ pub struct Frame {
     pub home: FrameHome,
     pub recover: FrameRecover,
     pub app: FrameApp,
+    #[cfg(feature = "experimental-records")]
+    pub vision: FrameVision,
+    /// Manned aircraft traffic detected by AirSense, as of the last ADS-B record. Traffic is
+    /// dropped when no ADS-B record was received for 5 seconds of flight.
+    #[cfg(feature = "experimental-records")]
+    pub traffic: Vec<FrameTraffic>,
+    /// Sections last filled from records without decryption key, whose values are unreliable.
+    /// Only set for frames built from `RecordEnvelope` objects.
+    pub unreliable_sections: Vec<FrameSection>,
+}
+
+/// A section of a `Frame`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum FrameSection {
+    Custom,
+    OSD,
+    Gimbal,
+    Camera,
+    RC,
+    Battery,
+    Home,
+    Recover,
+    App,
+    #[cfg(feature = "experimental-records")]
+    Vision,
+    #[cfg(feature = "experimental-records")]
+    Traffic,
+}
+
+impl FrameSection {
+    /// Returns the section filled from `record`, if any.
+    fn from_record(record: &Record) -> Option<Self> {
+        match record {
+            Record::OSD(_) => Some(FrameSection::OSD),
+            Record::Gimbal(_) => Some(FrameSection::Gimbal),
+            Record::Camera(_) => Some(FrameSection::Camera),
+            Record::RC(_) | Record::RCDisplayField(_) | Record::OFDM(_) => Some(FrameSection::RC),
+            Record::CenterBattery(_) | Record::SmartBattery(_) | Record::SmartBatteryGroup(_) => {
+                Some(FrameSection::Battery)
+            }
+            Record::Custom(_) => Some(FrameSection::Custom),
+            Record::Home(_) => Some(FrameSection::Home),
+            Record::Recover(_) => Some(FrameSection::Recover),
+            Record::AppTip(_)
+            | Record::AppWarn(_)
+            | Record::AppSeriousWarn(_)
+            | Record::AppOperation(_)
+            | Record::AppSpecialControlJoyStick(_) => Some(FrameSection::App),
+            #[cfg(feature = "experimental-records")]
+            Record::VisionGroup(_) | Record::VisionWarning(_) => Some(FrameSection::Vision),
+            #[cfg(feature = "experimental-records")]
+            Record::ADSBFlightData(_) => Some(FrameSection::Traffic),
+            _ => None,
+        }
+    }
+}
+
+/// A record a `Frame` can be built from.
+///
+/// Frames built from `RecordEnvelope` objects flag the sections filled from records without
+/// decryption key in `Frame::unreliable_sections`.
+///
+pub trait FrameRecord {
+    /// Returns the record.
+    fn record(&self) -> &Record;
+
+    /// Returns `true` if the record was not decrypted as its key is missing.
+    fn missing_key(&self) -> bool {
+        false
+    }
+}
+
+impl FrameRecord for Record {
+    fn record(&self) -> &Record {
+        self
+    }
+}
+
+impl FrameRecord for RecordEnvelope {
+    fn record(&self) -> &Record {
+        &self.record
+    }
+
+    fn missing_key(&self) -> bool {
+        self.missing_key
+    }
 }
 
 impl Frame {
     /// Resets event-related values of the `Frame` instance.
     ///
-    /// This method resets the state of the camera, application tips, and warnings.
+    /// This method resets the state of the camera, application tips, warnings, operations and
+    /// vision events.
     /// Additionally, if the battery cell voltage is estimated, it resets all cell voltages to zero.
     ///
     fn reset(&mut self) {
         self.camera.is_photo = bool::default();
         self.app.tip = String::default();
         self.app.warn = String::default();
+        self.app.operations.clear();
+        #[cfg(feature = "experimental-records")]
+        {
+            self.vision.brake_direction = None;
+            self.vision.warn = String::default();
+        }
 
         if self.battery.is_cell_voltage_estimated {
             self.battery.cell_voltages.fill(0.0);
@@ -128,49 +238,78 @@ impl Frame {
         if self.battery.cell_voltage_deviation > self.battery.max_cell_voltage_deviation {
             self.battery.max_cell_voltage_deviation = self.battery.cell_voltage_deviation;
         }
+
+        #[cfg(feature = "experimental-records")]
+        for traffic in self.traffic.iter_mut() {
+            traffic.distance = distance(
+                (self.osd.latitude, self.osd.longitude),
+                (traffic.latitude, traffic.longitude),
+            ) as f32;
+            traffic.vertical_separation = traffic.altitude - self.osd.altitude;
+        }
     }
 }
 
-/// Converts a vector of `Record` objects into a vector of `Frame` objects.
-///
-/// This function takes a list of `Record` objects and transforms each one into a
-/// corresponding `Frame` object. The transformation process normalizes the data
-/// across different log versions, creating a standardized format that's easier
-/// to work with.
+/// Incrementally normalizes `Record` objects into `Frame` objects.
 ///
-/// # Arguments
-/// - `records`: A vector of `Record` objects representing the raw log data.
-///
-/// # Returns
-/// - `Vec<Frame>`: A vector of `Frame` objects representing the normalized log data.
-///   Each `Frame` corresponds to one or more `Record` objects, depending on the
-///   specific normalization logic.
+/// `FrameBuilder` holds the state that carries over from one record to the next
+/// (home altitude, maximum values, battery cells...). A frame is emitted each time
+/// a new `OSD` record starts the next one.
 ///
-pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
-    let mut frames = Vec::new();
-    let mut frame = Frame {
-        battery: FrameBattery {
-            cell_num: details.product_type.battery_cell_num(),
-            cell_voltages: vec![0.0; details.product_type.battery_cell_num() as usize],
-            is_cell_voltage_estimated: true,
-            ..FrameBattery::default()
-        },
-        ..Frame::default()
-    };
-
-    let mut frame_index = 0;
-
-    for record in records {
+pub(crate) struct FrameBuilder {
+    details: Details,
+    frame: Frame,
+    frame_index: usize,
+    /// Fly time of the last ADS-B record
+    #[cfg(feature = "experimental-records")]
+    traffic_fly_time: f32,
+}
+
+impl FrameBuilder {
+    pub(crate) fn new(details: Details) -> Self {
+        let frame = Frame {
+            battery: FrameBattery {
+                cell_num: details.product_type.battery_cell_num(),
+                cell_voltages: vec![0.0; details.product_type.battery_cell_num() as usize],
+                is_cell_voltage_estimated: true,
+                ..FrameBattery::default()
+            },
+            ..Frame::default()
+        };
+
+        FrameBuilder {
+            details,
+            frame,
+            frame_index: 0,
+            #[cfg(feature = "experimental-records")]
+            traffic_fly_time: 0.0,
+        }
+    }
+
+    /// Applies a record to the current frame.
+    ///
+    /// Returns the previous frame, finalized, when the record starts a new one.
+    ///
+    pub(crate) fn push<R: FrameRecord>(&mut self, item: &R) -> Option<Frame> {
+        let record = item.record();
+        let frame = &mut self.frame;
+        let mut emitted = None;
+
         match record {
             Record::OSD(osd) => {
-                if frame_index > 0 {
+                if self.frame_index > 0 {
                     frame.finalize();
-                    frames.push(frame.clone());
+                    emitted = Some(frame.clone());
                     frame.reset();
                 }
 
                 // Fill OSD record
                 frame.osd.fly_time = osd.fly_time;
+                // Aircraft positions of a stale ADS-B record are not current anymore
+                #[cfg(feature = "experimental-records")]
+                if (frame.osd.fly_time - self.traffic_fly_time).abs() > TRAFFIC_TIMEOUT {
+                    frame.traffic.clear();
+                }
                 frame.osd.latitude = osd.latitude;
                 frame.osd.longitude = osd.longitude;
                 // Fix altitude by adding the home point altitude
@@ -187,7 +326,7 @@ pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
 
                 if frame.osd.flyc_state != Some(osd.flight_mode) {
                     frame.app.tip = append_message(
-                        frame.app.tip,
+                        &frame.app.tip,
                         format!("Flight mode changed to {:?}.", osd.flight_mode),
                     );
                 }
@@ -225,7 +364,7 @@ pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
                 frame.osd.is_vision_used = osd.is_vision_used;
                 frame.osd.voltage_warning = osd.voltage_warning;
 
-                frame_index += 1;
+                self.frame_index += 1;
             }
             Record::Gimbal(gimbal) => {
                 frame.gimbal.mode = Some(gimbal.mode);
@@ -234,17 +373,17 @@ pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
                 frame.gimbal.yaw = gimbal.yaw;
                 if !frame.gimbal.is_pitch_at_limit && gimbal.is_pitch_at_limit {
                     frame.app.tip =
-                        append_message(frame.app.tip, "Gimbal pitch axis endpoint reached.")
+                        append_message(&frame.app.tip, "Gimbal pitch axis endpoint reached.")
                 }
                 frame.gimbal.is_pitch_at_limit = gimbal.is_pitch_at_limit;
                 if !frame.gimbal.is_roll_at_limit && gimbal.is_roll_at_limit {
                     frame.app.tip =
-                        append_message(frame.app.tip, "Gimbal roll axis endpoint reached.")
+                        append_message(&frame.app.tip, "Gimbal roll axis endpoint reached.")
                 }
                 frame.gimbal.is_roll_at_limit = gimbal.is_roll_at_limit;
                 if !frame.gimbal.is_yaw_at_limit && gimbal.is_yaw_at_limit {
                     frame.app.tip =
-                        append_message(frame.app.tip, "Gimbal yaw axis endpoint reached.")
+                        append_message(&frame.app.tip, "Gimbal yaw axis endpoint reached.")
                 }
                 frame.gimbal.is_yaw_at_limit = gimbal.is_yaw_at_limit;
                 frame.gimbal.is_stuck = gimbal.is_stuck;
@@ -303,7 +442,7 @@ pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
                 SmartBatteryGroup::SmartBatteryStatic(_) => {}
                 SmartBatteryGroup::SmartBatteryDynamic(battery) => {
                     // when there are multiple batteries, only one contains accurate values at index 1
-                    if details.product_type.battery_num() < 2 || battery.index == 1 {
+                    if self.details.product_type.battery_num() < 2 || battery.index == 1 {
                         frame.battery.voltage = battery.current_voltage;
                         frame.battery.current = battery.current_current;
                         frame.battery.current_capacity = battery.remained_capacity;
@@ -367,26 +506,185 @@ pub fn records_to_frames(records: Vec<Record>, details: Details) -> Vec<Frame> {
                 frame.home.current_flight_record_index = home.current_flight_record_index;
             }
             Record::Recover(recover) => {
-                frame.recover.app_platform = Some(recover.app_platform);
-                frame.recover.app_version = recover.app_version;
-                frame.recover.aircraft_name = recover.aircraft_name;
-                frame.recover.aircraft_sn = recover.aircraft_sn;
-                frame.recover.camera_sn = recover.camera_sn;
-                frame.recover.rc_sn = recover.rc_sn;
-                frame.recover.battery_sn = recover.battery_sn;
+                frame.recover.app_platform = Some(recover.app_platform.clone());
+                frame.recover.app_version = recover.app_version.clone();
+                frame.recover.aircraft_name = recover.aircraft_name.clone();
+                frame.recover.aircraft_sn = recover.aircraft_sn.clone();
+                frame.recover.camera_sn = recover.camera_sn.clone();
+                frame.recover.rc_sn = recover.rc_sn.clone();
+                frame.recover.battery_sn = recover.battery_sn.clone();
             }
             Record::AppTip(app_tip) => {
-                frame.app.tip = append_message(frame.app.tip, app_tip.message);
+                frame.app.tip = append_message(&frame.app.tip, &app_tip.message);
             }
             Record::AppWarn(app_warn) => {
-                frame.app.warn = append_message(frame.app.warn, app_warn.message);
+                frame.app.warn = append_message(&frame.app.warn, &app_warn.message);
             }
             Record::AppSeriousWarn(app_serious_warn) => {
-                frame.app.warn = append_message(frame.app.warn, app_serious_warn.message);
+                frame.app.warn = append_message(&frame.app.warn, &app_serious_warn.message);
+            }
+            Record::AppOperation(app_operation) => {
+                frame.app.operations.push(FrameAppOperation {
+                    operation: app_operation.operation,
+                    value: app_operation.value,
+                });
+            }
+            Record::AppSpecialControlJoyStick(joystick) => {
+                frame.app.joystick.is_enabled = joystick.is_enabled;
+                frame.app.joystick.roll = joystick.roll;
+                frame.app.joystick.pitch = joystick.pitch;
+                frame.app.joystick.yaw = joystick.yaw;
+                frame.app.joystick.throttle = joystick.throttle;
+            }
+            #[cfg(feature = "experimental-records")]
+            Record::VisionGroup(vision_group) => match vision_group {
+                VisionGroup::VisionAvoidanceState(state) => {
+                    frame.vision.is_avoidance_enabled = state.is_avoidance_enabled;
+                    frame.vision.avoidance_mode = Some(state.avoidance_mode);
+                    frame.vision.is_braking = state.is_braking;
+                    frame.vision.is_bypassing = state.is_bypassing;
+                }
+                VisionGroup::VisionObstacleDistances(obstacles) => {
+                    let distance = obstacles.closest_distance().unwrap_or_default();
+                    match obstacles.direction {
+                        VisionDirection::Front => frame.vision.front_distance = distance,
+                        VisionDirection::Back => frame.vision.back_distance = distance,
+                        VisionDirection::Left => frame.vision.left_distance = distance,
+                        VisionDirection::Right => frame.vision.right_distance = distance,
+                        VisionDirection::Up => frame.vision.up_distance = distance,
+                        VisionDirection::Down => frame.vision.down_distance = distance,
+                        VisionDirection::Unknown(_) => {}
+                    }
+                }
+                VisionGroup::VisionBraking(braking) => {
+                    frame.vision.brake_direction = Some(braking.direction);
+                }
+            },
+            #[cfg(feature = "experimental-records")]
+            Record::VisionWarning(vision_warning) => {
+                frame.vision.warn = append_message(&frame.vision.warn, &vision_warning.message);
+            }
+            #[cfg(feature = "experimental-records")]
+            Record::ADSBFlightData(adsb) => {
+                self.traffic_fly_time = frame.osd.fly_time;
+                frame.traffic = adsb
+                    .aircraft
+                    .iter()
+                    .map(|aircraft| FrameTraffic {
+                        icao_address: aircraft.icao_hex(),
+                        call_sign: aircraft.call_sign.clone(),
+                        latitude: aircraft.latitude,
+                        longitude: aircraft.longitude,
+                        altitude: aircraft.altitude,
+                        heading: aircraft.heading,
+                        horizontal_speed: aircraft.horizontal_speed,
+                        vertical_speed: aircraft.vertical_speed,
+                        warning_level: Some(aircraft.warning_level),
+                        ..FrameTraffic::default()
+                    })
+                    .collect();
             }
             _ => {}
         }
+
+        // Sections keep their values until the next record, and so their reliability
+        if let Some(section) = FrameSection::from_record(record) {
+            let sections = &mut self.frame.unreliable_sections;
+            match (sections.binary_search(&section), item.missing_key()) {
+                (Err(index), true) => sections.insert(index, section),
+                (Ok(index), false) => {
+                    sections.remove(index);
+                }
+                _ => {}
+            }
+        }
+
+        emitted
     }
+}
 
-    frames
+/// Iterator adapter converting a stream of `Record` or `RecordEnvelope` objects into
+/// `Frame` objects.
+///
+/// Records are pulled lazily from the inner iterator, so only the frame being
+/// built is kept in memory.
+///
+pub struct FrameIter<I> {
+    records: I,
+    builder: FrameBuilder,
+}
+
+impl<I> FrameIter<I>
+where
+    I: Iterator,
+    I::Item: FrameRecord,
+{
+    /// Creates a new `FrameIter` from an iterator of records and the log details.
+    pub fn new(records: I, details: Details) -> Self {
+        FrameIter {
+            records,
+            builder: FrameBuilder::new(details),
+        }
+    }
+
+    /// Returns a reference to the inner records iterator.
+    pub fn records(&self) -> &I {
+        &self.records
+    }
+}
+
+impl<I> Iterator for FrameIter<I>
+where
+    I: Iterator,
+    I::Item: FrameRecord,
+{
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        for record in self.records.by_ref() {
+            if let Some(frame) = self.builder.push(&record) {
+                return Some(frame);
+            }
+        }
+        None
+    }
+}
+
+/// Converts a vector of `Record` objects into a vector of `Frame` objects.
+///
+/// This function takes a list of `Record` objects and transforms each one into a
+/// corresponding `Frame` object. The transformation process normalizes the data
+/// across different log versions, creating a standardized format that's easier
+/// to work with.
+///
+/// # Arguments
+/// - `records`: A vector of `Record` objects representing the raw log data.
+///
+/// # Returns
+/// - `Vec<Frame>`: A vector of `Frame` objects representing the normalized log data.
+///   Each `Frame` corresponds to one or more `Record` objects, depending on the
+///   specific normalization logic.
+///
+pub fn records_to_frames<R: FrameRecord>(records: Vec<R>, details: Details) -> Vec<Frame> {
+    FrameIter::new(records.into_iter(), details).collect()
+}
+
+/// Converts a slice of `Record` objects into a vector of `Frame` objects.
+///
+/// Same as `records_to_frames`, but borrows the records so they can still be used
+/// once converted, without decoding the log a second time.
+///
+/// # Arguments
+/// - `records`: A slice of `Record` or `RecordEnvelope` objects representing the raw log data.
+/// - `details`: The log details, used to initialize the battery cells.
+///
+/// # Returns
+/// - `Vec<Frame>`: A vector of `Frame` objects representing the normalized log data.
+///
+pub fn frames_from_records<R: FrameRecord>(records: &[R], details: Details) -> Vec<Frame> {
+    let mut builder = FrameBuilder::new(details);
+    records
+        .iter()
+        .filter_map(|record| builder.push(record))
+        .collect()
 }
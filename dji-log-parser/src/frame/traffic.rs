@@ -0,0 +1,33 @@
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::record::adsb::ADSBWarningLevel;
+
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct FrameTraffic {
+    /// ICAO address of the aircraft, as 6 hexadecimal digits
+    pub icao_address: String,
+    /// Call sign of the aircraft
+    pub call_sign: String,
+    /// Latitude in degrees
+    pub latitude: f64,
+    /// Longitude in degrees
+    pub longitude: f64,
+    /// Altitude in meters
+    pub altitude: f32,
+    /// Heading in degrees, clockwise from true north
+    pub heading: f32,
+    /// Horizontal speed in meters per second
+    pub horizontal_speed: f32,
+    /// Vertical speed in meters per second
+    pub vertical_speed: f32,
+    /// AirSense threat level
+    pub warning_level: Option<ADSBWarningLevel>,
+    /// Horizontal distance from the drone in meters
+    pub distance: f32,
+    /// Altitude difference with the drone in meters, positive when the aircraft is above
+    pub vertical_separation: f32,
+}
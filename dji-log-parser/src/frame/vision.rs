@@ -0,0 +1,35 @@
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::record::vision_group::{VisionAvoidanceMode, VisionDirection};
+
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct FrameVision {
+    /// Indicates if obstacle avoidance is enabled
+    pub is_avoidance_enabled: bool,
+    /// Obstacle avoidance behavior
+    pub avoidance_mode: Option<VisionAvoidanceMode>,
+    /// Indicates if the aircraft is braking in front of an obstacle
+    pub is_braking: bool,
+    /// Indicates if the aircraft is bypassing an obstacle
+    pub is_bypassing: bool,
+    /// Distance of the closest obstacle in front in meters, 0 when none is detected
+    pub front_distance: f32,
+    /// Distance of the closest obstacle behind in meters, 0 when none is detected
+    pub back_distance: f32,
+    /// Distance of the closest obstacle on the left in meters, 0 when none is detected
+    pub left_distance: f32,
+    /// Distance of the closest obstacle on the right in meters, 0 when none is detected
+    pub right_distance: f32,
+    /// Distance of the closest obstacle above in meters, 0 when none is detected
+    pub up_distance: f32,
+    /// Distance of the closest obstacle below in meters, 0 when none is detected
+    pub down_distance: f32,
+    /// Direction of the obstacle the aircraft started braking for during the frame
+    pub brake_direction: Option<VisionDirection>,
+    /// Vision system warning
+    pub warn: String,
+}
@@ -0,0 +1,568 @@
+use binrw::BinRead;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::decoder::{decode_payload, skip_record};
+use crate::keychain::{FeaturePoint, Keychain};
+use crate::record::{
+    Record, RecordEnvelope, END_BYTE, JPEG_TYPE_ID, KEY_STORAGE_RECOVER_TYPE_ID, KNOWN_RECORD_TYPES,
+};
+use crate::{DJILog, Error, ParseFailure, ParseReport, RecordFilter, Result, SkippedRegion};
+
+/// Decoding state of the records section of a `DJILog`.
+///
+/// `RecordCursor` keeps track of the current position, keychains and parse report
+/// between records. It does not borrow the log, so it can be driven either by a
+/// `RecordIter` or by a `DJILogFollower` while the log keeps growing.
+///
+pub(crate) struct RecordCursor {
+    pub(crate) position: u64,
+    pub(crate) end_offset: u64,
+    keychains: VecDeque<Keychain>,
+    keychain: RefCell<Keychain>,
+    pub(crate) recovery: bool,
+    pub(crate) filter: Option<RecordFilter>,
+    pub(crate) payloads: bool,
+    pub(crate) report: ParseReport,
+}
+
+impl RecordCursor {
+    pub(crate) fn new(log: &DJILog, keychains: Vec<Keychain>) -> Self {
+        let mut keychains = VecDeque::from(keychains);
+
+        let position = log.prefix.records_offset();
+        let end_offset = log.prefix.records_end_offset(log.size);
+
+        RecordCursor {
+            position,
+            end_offset,
+            keychain: RefCell::new(keychains.pop_front().unwrap_or(Keychain::empty())),
+            keychains,
+            recovery: false,
+            filter: None,
+            payloads: false,
+            report: ParseReport {
+                records_offset: position,
+                records_end_offset: end_offset,
+                ..ParseReport::default()
+            },
+        }
+    }
+
+    /// Decodes the record at the current position and moves past it.
+    ///
+    /// Returns the record type id, i.e. the first byte of the record, along with the record
+    /// and its plaintext content when payloads are kept.
+    ///
+    fn read_record(&mut self, log: &DJILog) -> Result<(u8, Record, Option<Vec<u8>>)> {
+        // The reader is shared with the log, so each read starts with an absolute seek
+        let mut reader = log.reader();
+        reader.seek(SeekFrom::Start(self.position))?;
+
+        let mut record_type = [0u8];
+        reader.read_exact(&mut record_type)?;
+        reader.seek(SeekFrom::Start(self.position))?;
+
+        let feature_point = FeaturePoint::from_record_type(record_type[0], log.version);
+        let keychain_entry = self.keychain.borrow().get(&feature_point).cloned();
+
+        let mut payload = if self.payloads {
+            let payload = self.read_payload(&mut *reader, log);
+            reader.seek(SeekFrom::Start(self.position))?;
+            payload
+        } else {
+            None
+        };
+
+        let record = Record::read_args(
+            &mut *reader,
+            binrw::args! {
+                version: log.version,
+                keychain: &self.keychain
+            },
+        )?;
+
+        let mut end_position = reader.stream_position()?;
+
+        // An encrypted record which cannot be decrypted falls back to `Record::Invalid`.
+        // Decode it again to report the typed decoding error, then move past the framed
+        // record, as its IV chain is already in sync, so the following records still decode.
+        if let (Record::Invalid(_), Some(entry)) = (&record, keychain_entry) {
+            if log.version >= 13 {
+                let decoded = self.check_decoding(&mut *reader, log, feature_point, entry);
+                if let Err(error) = decoded {
+                    let stats = self.report.decryption.entry(feature_point).or_default();
+                    stats.records += 1;
+                    match error {
+                        Error::InvalidPadding => stats.padding_errors += 1,
+                        _ => stats.other_errors += 1,
+                    }
+
+                    if let Some((_, header_size, length)) =
+                        read_record_header(&mut *reader, self.position, log.version)
+                    {
+                        end_position = self.position + header_size + length + 1;
+                    }
+
+                    self.report.failures.push(ParseFailure {
+                        offset: self.position,
+                        record_type: Some(record_type[0]),
+                        error,
+                    });
+                }
+            }
+        }
+
+        self.position = end_position;
+
+        if let (true, Record::JPEG(jpeg)) = (self.payloads, &record) {
+            payload = Some(jpeg.clone());
+        }
+
+        Ok((record_type[0], record, payload))
+    }
+
+    /// Reads the plaintext content of the framed record at the current position, without
+    /// moving the keychain forward.
+    ///
+    /// `KeyStorageRecover` records are returned as is, as they are written.
+    ///
+    fn read_payload<R: Read + Seek>(&self, reader: &mut R, log: &DJILog) -> Option<Vec<u8>> {
+        let (type_id, header_size, length) =
+            read_record_header(reader, self.position, log.version)?;
+
+        if type_id == JPEG_TYPE_ID
+            || !has_end_byte(reader, self.position, header_size, length, self.end_offset)
+        {
+            return None;
+        }
+
+        reader
+            .seek(SeekFrom::Start(self.position + header_size))
+            .ok()?;
+
+        if type_id == KEY_STORAGE_RECOVER_TYPE_ID {
+            let mut content = vec![0u8; length as usize];
+            reader.read_exact(&mut content).ok()?;
+            return Some(content);
+        }
+
+        let keychain = RefCell::new(self.keychain.borrow().clone());
+        decode_payload(reader, type_id, log.version, &keychain, length as u16).ok()
+    }
+
+    /// Decodes the content of the framed record at the current position from the keychain
+    /// entry preceding it, keeping the keychain in sync.
+    ///
+    /// Returns the decoding error, if any. Records without a plausible header are not checked,
+    /// so an error means the record framing is valid.
+    ///
+    fn check_decoding<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        log: &DJILog,
+        feature_point: FeaturePoint,
+        entry: (Vec<u8>, Vec<u8>),
+    ) -> Result<()> {
+        let Some((type_id, header_size, length)) =
+            read_record_header(reader, self.position, log.version)
+        else {
+            return Ok(());
+        };
+
+        if !has_end_byte(reader, self.position, header_size, length, self.end_offset) {
+            return Ok(());
+        }
+
+        self.keychain.borrow_mut().insert(feature_point, entry);
+
+        reader.seek(SeekFrom::Start(self.position + header_size))?;
+        decode_payload(reader, type_id, log.version, &self.keychain, length as u16)?;
+
+        Ok(())
+    }
+
+    /// Skips the record at the current position if it is rejected by the filter.
+    ///
+    /// Returns `true` if a record was skipped. Records without a plausible header are
+    /// never skipped, so they are decoded and reported as usual.
+    ///
+    fn skip_filtered_record(&mut self, log: &DJILog) -> bool {
+        let Some(filter) = &self.filter else {
+            return false;
+        };
+
+        let mut reader = log.reader();
+        let Some((type_id, header_size, length)) =
+            read_record_header(&mut *reader, self.position, log.version)
+        else {
+            return false;
+        };
+
+        if filter.accepts(type_id)
+            || !has_end_byte(
+                &mut *reader,
+                self.position,
+                header_size,
+                length,
+                self.end_offset,
+            )
+        {
+            return false;
+        }
+
+        // Keep the IV chain of the record feature point in sync
+        let content_offset = self.position + header_size;
+        if reader.seek(SeekFrom::Start(content_offset)).is_err()
+            || skip_record(
+                &mut *reader,
+                type_id,
+                log.version,
+                &self.keychain,
+                length as u16,
+            )
+            .is_err()
+        {
+            return false;
+        }
+
+        self.position = content_offset + length + 1;
+        self.report.filtered_count += 1;
+        true
+    }
+
+    /// Reads the record type id at the current position.
+    fn read_record_type(&self, log: &DJILog) -> Option<u8> {
+        let mut reader = log.reader();
+        reader.seek(SeekFrom::Start(self.position)).ok()?;
+
+        let mut record_type = [0u8];
+        reader.read_exact(&mut record_type).ok()?;
+
+        Some(record_type[0])
+    }
+
+    /// Scans forward from `start` for the next plausible record header.
+    fn find_next_record(&self, log: &DJILog, start: u64) -> Option<u64> {
+        let mut reader = log.reader();
+        (start..self.end_offset)
+            .find(|&offset| is_record_header(&mut *reader, offset, log.version, self.end_offset))
+    }
+
+    /// Checks whether the record at the current position is fully available before
+    /// the end of the records section.
+    pub(crate) fn is_record_complete(&self, log: &DJILog) -> bool {
+        let mut reader = log.reader();
+
+        let mut magic = [0u8; 2];
+        if reader.seek(SeekFrom::Start(self.position)).is_err()
+            || reader.read_exact(&mut magic).is_err()
+        {
+            return false;
+        }
+
+        // JPEG records have no length and end with the `0xFFD9` marker
+        if magic == [0xFF, 0xD8] {
+            let mut previous = 0u8;
+            let mut byte = [0u8];
+            while reader.stream_position().is_ok_and(|p| p < self.end_offset)
+                && reader.read_exact(&mut byte).is_ok()
+            {
+                if previous == 0xFF && byte[0] == 0xD9 {
+                    return true;
+                }
+                previous = byte[0];
+            }
+            return false;
+        }
+
+        match read_record_header(&mut *reader, self.position, log.version) {
+            Some((_, header_size, length)) => {
+                self.position + header_size + length < self.end_offset
+            }
+            None => false,
+        }
+    }
+
+    /// Decodes the next record along with its framing information.
+    pub(crate) fn next_envelope(&mut self, log: &DJILog) -> Option<RecordEnvelope> {
+        loop {
+            if self.position >= self.end_offset {
+                return None;
+            }
+
+            if self.skip_filtered_record(log) {
+                continue;
+            }
+
+            let envelope = self.decode_envelope(log)?;
+
+            // JPEG and invalid records have no length prefix, so they are filtered once decoded
+            if let Some(filter) = &self.filter {
+                if !filter.accepts(envelope.type_id) {
+                    self.report.filtered_count += 1;
+                    continue;
+                }
+            }
+
+            return Some(envelope);
+        }
+    }
+
+    /// Decodes the record at the current position along with its framing information.
+    fn decode_envelope(&mut self, log: &DJILog) -> Option<RecordEnvelope> {
+        // decode record
+        let (offset, type_id, record, payload) = loop {
+            let offset = self.position;
+            match self.read_record(log) {
+                Ok((type_id, record, payload)) => break (offset, type_id, record, payload),
+                Err(error) => {
+                    self.report.failures.push(ParseFailure {
+                        offset: self.position,
+                        record_type: self.read_record_type(log),
+                        error,
+                    });
+
+                    let next_position = if self.recovery {
+                        self.find_next_record(log, self.position + 1)
+                    } else {
+                        None
+                    };
+
+                    match next_position {
+                        Some(next_position) => {
+                            // resume decoding at the next plausible record
+                            let length = next_position - self.position;
+                            self.report.skipped_regions.push(SkippedRegion {
+                                offset: self.position,
+                                length,
+                            });
+                            self.report.unparsed_bytes += length;
+                            self.position = next_position;
+                        }
+                        None => {
+                            // stop iterating on the first undecodable record
+                            self.report.unparsed_bytes += self.end_offset - self.position;
+                            self.position = self.end_offset;
+                            return None;
+                        }
+                    }
+                }
+            }
+        };
+
+        match record {
+            Record::Unknown(..) => self.report.unknown_count += 1,
+            Record::Invalid(_) => self.report.invalid_count += 1,
+            _ => {}
+        }
+
+        let feature_point =
+            (log.version >= 13).then(|| FeaturePoint::from_record_type(type_id, log.version));
+
+        let mut missing_key = false;
+        if let Some(feature_point) = feature_point.filter(|feature_point| {
+            *feature_point != FeaturePoint::PlaintextFeature
+                && !matches!(record, Record::JPEG(_) | Record::Invalid(_))
+        }) {
+            missing_key = self.keychain.borrow().get(&feature_point).is_none();
+
+            let stats = self.report.decryption.entry(feature_point).or_default();
+            stats.records += 1;
+            if missing_key {
+                stats.missing_key += 1;
+            } else {
+                stats.decrypted += 1;
+            }
+        }
+
+        if let Record::KeyStorageRecover(_) = record {
+            self.keychain = RefCell::new(self.keychains.pop_front().unwrap_or(Keychain::empty()));
+        }
+
+        Some(RecordEnvelope {
+            offset,
+            length: self.position - offset,
+            type_id,
+            feature_point,
+            missing_key,
+            record,
+            payload,
+        })
+    }
+}
+
+/// Reads the record header located at `offset`.
+///
+/// Returns the record type id, the header size and the record content length.
+///
+pub(crate) fn read_record_header<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    version: u8,
+) -> Option<(u8, u64, u64)> {
+    // Length is stored on one byte up to version 12, on two bytes after
+    let header_size = if version <= 12 { 2 } else { 3 };
+
+    let mut header = [0u8; 3];
+    reader.seek(SeekFrom::Start(offset)).ok()?;
+    reader.read_exact(&mut header[..header_size]).ok()?;
+
+    let length = if version <= 12 {
+        header[1] as u64
+    } else {
+        u16::from_le_bytes([header[1], header[2]]) as u64
+    };
+
+    Some((header[0], header_size as u64, length))
+}
+
+/// Checks whether a plausible record header is located at `offset`.
+///
+/// A header is plausible when its record type is known, its length fits in the records
+/// section and the end byte `0xFF` is found right after the record content.
+///
+pub(crate) fn is_record_header<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    version: u8,
+    end_offset: u64,
+) -> bool {
+    let Some((record_type, header_size, length)) = read_record_header(reader, offset, version)
+    else {
+        return false;
+    };
+
+    KNOWN_RECORD_TYPES
+        .iter()
+        .any(|(type_id, _)| *type_id == record_type)
+        && has_end_byte(reader, offset, header_size, length, end_offset)
+}
+
+/// Checks whether the record at `offset` is non empty, fits in the records section and
+/// is followed by the end byte `0xFF`.
+pub(crate) fn has_end_byte<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    header_size: u64,
+    length: u64,
+    end_offset: u64,
+) -> bool {
+    let end_byte_offset = offset + header_size + length;
+    if length == 0 || end_byte_offset >= end_offset {
+        return false;
+    }
+
+    let mut end_byte = [0u8];
+    reader.seek(SeekFrom::Start(end_byte_offset)).is_ok()
+        && reader.read_exact(&mut end_byte).is_ok()
+        && end_byte[0] == END_BYTE
+}
+
+/// Lazy iterator over the records of a `DJILog`.
+///
+/// Records are decoded one at a time from the log reader. Keychains are consumed
+/// in order, switching to the next one each time a `KeyStorageRecover` record
+/// is encountered. Iteration stops at the end of the records section or at the
+/// first record that cannot be decoded, which is then described in the `ParseReport`
+/// available from `report`, unless recovery mode is enabled with `with_recovery`.
+/// Encrypted records which cannot be decrypted do not stop iteration: they are reported
+/// as failures and yielded as `Record::Invalid`.
+///
+pub struct RecordIter<'a> {
+    log: &'a DJILog,
+    cursor: RecordCursor,
+}
+
+impl<'a> RecordIter<'a> {
+    pub(crate) fn new(log: &'a DJILog, keychains: Vec<Keychain>) -> Self {
+        RecordIter {
+            log,
+            cursor: RecordCursor::new(log, keychains),
+        }
+    }
+
+    /// Enables recovery mode.
+    ///
+    /// Instead of stopping at the first record that cannot be decoded, the iterator scans
+    /// forward for the next plausible record header and resumes decoding from there. Skipped
+    /// regions are listed in the `ParseReport`. This is mostly useful for logs truncated or
+    /// corrupted by a crash.
+    ///
+    /// For encrypted logs (versions >= 13), the AES IV chain is broken by a skipped region,
+    /// so some records following it may not decrypt properly.
+    ///
+    pub fn with_recovery(mut self) -> Self {
+        self.cursor.recovery = true;
+        self
+    }
+
+    /// Skips records rejected by `filter` instead of decoding them.
+    ///
+    /// Skipped records are counted in the `ParseReport` but are neither decrypted nor parsed.
+    ///
+    pub fn with_filter(mut self, filter: RecordFilter) -> Self {
+        self.cursor.filter = Some(filter);
+        self
+    }
+
+    /// Keeps the plaintext content of each record in `RecordEnvelope::payload`, so records
+    /// can be written again with `DJILogWriter::write_envelope`.
+    pub fn with_payloads(mut self) -> Self {
+        self.cursor.payloads = true;
+        self
+    }
+
+    /// Wraps each record in a `RecordEnvelope` exposing its offset, length, type id
+    /// and feature point.
+    pub fn envelopes(self) -> RecordEnvelopeIter<'a> {
+        RecordEnvelopeIter(self)
+    }
+
+    /// Returns the report of the records decoded so far.
+    pub fn report(&self) -> &ParseReport {
+        &self.cursor.report
+    }
+
+    /// Consumes the iterator and returns the report of the records decoded so far.
+    pub fn into_report(self) -> ParseReport {
+        self.cursor.report
+    }
+}
+
+impl Iterator for RecordIter<'_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        self.cursor
+            .next_envelope(self.log)
+            .map(|envelope| envelope.record)
+    }
+}
+
+/// Lazy iterator over the records of a `DJILog`, wrapped in a `RecordEnvelope`.
+///
+/// Created with `RecordIter::envelopes`.
+///
+pub struct RecordEnvelopeIter<'a>(RecordIter<'a>);
+
+impl RecordEnvelopeIter<'_> {
+    /// Returns the report of the records decoded so far.
+    pub fn report(&self) -> &ParseReport {
+        self.0.report()
+    }
+
+    /// Consumes the iterator and returns the report of the records decoded so far.
+    pub fn into_report(self) -> ParseReport {
+        self.0.into_report()
+    }
+}
+
+impl Iterator for RecordEnvelopeIter<'_> {
+    type Item = RecordEnvelope;
+
+    fn next(&mut self) -> Option<RecordEnvelope> {
+        self.0.cursor.next_envelope(self.0.log)
+    }
+}
@@ -1,12 +1,21 @@
+use crc64::crc64;
 use serde::{Deserialize, Serialize};
 #[cfg(target_arch = "wasm32")]
 use tsify_next::Tsify;
 
-use crate::Result;
+use crate::{Error, Result};
 
 use super::{EncodedKeychainFeaturePoint, KeychainFeaturePoint};
+#[cfg(not(target_arch = "wasm32"))]
+use super::{KeychainCache, KeychainClient};
+
+pub(crate) const DEFAULT_ENDPOINT: &str = "https://dev.dji.com/openapi/v1/flight-records/keychains";
 
-const DEFAULT_ENDPOINT: &str = "https://dev.dji.com/openapi/v1/flight-records/keychains";
+/// Result codes of the keychain API, in `KeychainResponseResult::code`.
+const RESULT_SUCCESS: u8 = 0;
+const RESULT_INVALID_API_KEY: u8 = 1;
+const RESULT_QUOTA_EXCEEDED: u8 = 2;
+const RESULT_UNSUPPORTED_VERSION: u8 = 3;
 
 /// Request structure for keychain API.
 #[derive(Debug, Default, Serialize, Clone)]
@@ -32,8 +41,66 @@ pub struct KeychainResponseResult {
     pub msg: String,
 }
 
+impl KeychainsResponse {
+    /// Returns the keychains of the response, or the typed error matching its result code.
+    /// Unknown result codes are returned as `Error::ApiError` with the message of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request the response answers.
+    ///
+    pub fn into_keychains(
+        self,
+        request: &KeychainsRequest,
+    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
+        match self.result.code {
+            RESULT_SUCCESS => self
+                .data
+                .ok_or_else(|| Error::ApiError("Missing keychain data".to_owned())),
+            RESULT_INVALID_API_KEY => Err(Error::ApiKeyError),
+            RESULT_QUOTA_EXCEEDED => Err(Error::ApiQuotaExceeded),
+            RESULT_UNSUPPORTED_VERSION => Err(Error::ApiUnsupportedVersion(request.version)),
+            code => Err(Error::ApiError(format!(
+                "{} (code {})",
+                self.result.msg, code
+            ))),
+        }
+    }
+}
+
 impl KeychainsRequest {
-    /// Sends a synchronous request to the keychain API.
+    /// Returns the fingerprint of the log the request was built from.
+    ///
+    /// The fingerprint is the CRC-64 of the encoded `KeyStorage` ciphertexts of the log, as 16
+    /// hexadecimal digits. It identifies the keychains able to decrypt the log, whatever the
+    /// version and department used to request them.
+    ///
+    pub fn fingerprint(&self) -> String {
+        let digest = self.keychains.iter().fold(0, |digest, keychain| {
+            let digest = keychain.iter().fold(digest, |digest, feature_point| {
+                let digest = crc64(digest, &(feature_point.feature_point as u16).to_le_bytes());
+                crc64(digest, feature_point.aes_ciphertext.as_bytes())
+            });
+            // Keychains separator
+            crc64(digest, &[0xFF])
+        });
+
+        format!("{:016x}", digest)
+    }
+
+    /// Returns the key of the request in a `KeychainCache`.
+    ///
+    /// The key is the CRC-64 of the request body, i.e. the encoded `KeyStorage` ciphertexts,
+    /// version and department, as 16 hexadecimal digits.
+    ///
+    pub fn cache_key(&self) -> Result<String> {
+        let body = serde_json::to_string(self)?;
+        Ok(format!("{:016x}", crc64(0, body.as_bytes())))
+    }
+
+    /// Sends a synchronous request to the keychain API, with the default `KeychainClient`
+    /// settings: failed requests are retried twice with backoff, each attempt timing out
+    /// after 30 seconds.
     ///
     /// This method is only available for non-WASM targets.
     ///
@@ -52,8 +119,28 @@ impl KeychainsRequest {
         api_key: &str,
         endpoint: Option<&str>,
     ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
-        let endpoint = endpoint.unwrap_or(DEFAULT_ENDPOINT);
-        native::fetch(api_key, endpoint, self)
+        client(endpoint)?.fetch(api_key, self)
+    }
+
+    /// Returns the keychains stored in `cache` for this request, or sends a synchronous request
+    /// to the keychain API and stores its response in `cache`.
+    ///
+    /// This method is only available for non-WASM targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key for authentication.
+    /// * `endpoint` - The URL endpoint for the API.
+    /// * `cache` - The cache to look up first.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch_with_cache(
+        &self,
+        api_key: &str,
+        endpoint: Option<&str>,
+        cache: &dyn KeychainCache,
+    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
+        client(endpoint)?.fetch_with_cache(api_key, self, cache)
     }
 
     /// Sends an asynchronous request to the keychain API.
@@ -75,72 +162,20 @@ impl KeychainsRequest {
         api_key: &str,
         endpoint: Option<&str>,
     ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
-        let endpoint = endpoint.unwrap_or(DEFAULT_ENDPOINT);
-
         #[cfg(not(target_arch = "wasm32"))]
-        return native::fetch_async(api_key, endpoint, self).await;
+        return client(endpoint)?.fetch_async(api_key, self).await;
         #[cfg(target_arch = "wasm32")]
-        return wasm::fetch_async(api_key, endpoint, self).await;
+        return wasm::fetch_async(api_key, endpoint.unwrap_or(DEFAULT_ENDPOINT), self).await;
     }
 }
 
+/// Returns a `KeychainClient` with the default settings, sending requests to `endpoint`.
 #[cfg(not(target_arch = "wasm32"))]
-pub(crate) mod native {
-    use std::time::Duration;
-
-    use crate::keychain::KeychainFeaturePoint;
-    use crate::{Error, Result};
-
-    use super::{KeychainsRequest, KeychainsResponse};
-
-    pub fn fetch(
-        api_key: &str,
-        endpoint: &str,
-        request: &KeychainsRequest,
-    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
-        let body = serde_json::to_string(&request)?;
-
-        let response = ureq::post(endpoint)
-            .set("Content-Type", "application/json")
-            .set("Api-Key", api_key)
-            .timeout(Duration::from_secs(30))
-            .send_string(&body)
-            .map_err(|e| match e {
-                ureq::Error::Status(403, _) => Error::ApiKeyError,
-                ureq::Error::Status(status, _) => Error::NetworkRequestStatus(status),
-                _ => Error::NetworkConnection,
-            })?;
-
-        let keychains_response: KeychainsResponse = response.into_json()?;
-
-        if keychains_response.result.code != 0 {
-            Err(Error::ApiError(keychains_response.result.msg))
-        } else {
-            match keychains_response.data {
-                Some(data) => Ok(data),
-                None => Err(Error::ApiError("Missing keychain data".to_owned())),
-            }
-        }
-    }
-
-    #[cfg(feature = "native-async")]
-    pub async fn fetch_async(
-        api_key: &str,
-        endpoint: &str,
-        request: &KeychainsRequest,
-    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
-        let api_key = api_key.to_string();
-        let endpoint = endpoint.to_string();
-        let request = request.clone();
-
-        let (tx, rx) = async_channel::bounded(1);
-
-        std::thread::spawn(move || {
-            let response = fetch(&api_key, &endpoint, &request);
-            tx.send(response);
-        });
-
-        rx.recv().await.map_err(|_| Error::NetworkConnection)?
+fn client(endpoint: Option<&str>) -> Result<KeychainClient> {
+    let builder = KeychainClient::builder();
+    match endpoint {
+        Some(endpoint) => builder.endpoint(endpoint).build(),
+        None => builder.build(),
     }
 }
 
@@ -173,25 +208,28 @@ pub(crate) mod wasm {
         init.method("POST");
         init.body(Some(&JsValue::from_str(&body)));
 
-        let request = Request::new_with_str_and_init(endpoint, &init)
+        let http_request = Request::new_with_str_and_init(endpoint, &init)
             .map_err(|_| Error::ApiError("Unable to init request".to_owned()))?;
 
-        request
+        http_request
             .headers()
             .set("Content-Type", "application/json")
             .map_err(|_| Error::ApiError("Unable to set Content-Type header".to_owned()))?;
-        request
+        http_request
             .headers()
             .set("Api-Key", &api_key)
             .map_err(|_| Error::ApiError("Unable to set Api-Key header".to_owned()))?;
 
-        let response: Response = JsFuture::from(js_fetch(&request))
+        let response: Response = JsFuture::from(js_fetch(&http_request))
             .await
             .map_err(|_| Error::ApiError("Unable to fetch request".to_owned()))?
             .unchecked_into();
 
-        if !response.ok() {
-            return Err(Error::ApiKeyError);
+        match response.status() {
+            401 | 403 => return Err(Error::ApiKeyError),
+            429 => return Err(Error::ApiQuotaExceeded),
+            _ if !response.ok() => return Err(Error::NetworkRequestStatus(response.status())),
+            _ => {}
         }
 
         let json = JsFuture::from(
@@ -205,13 +243,6 @@ pub(crate) mod wasm {
         let keychains_response: KeychainsResponse = serde_wasm_bindgen::from_value(json)
             .map_err(|_| Error::ApiError("Unable to parse response".to_owned()))?;
 
-        if keychains_response.result.code != 0 {
-            Err(Error::ApiError(keychains_response.result.msg))
-        } else {
-            match keychains_response.data {
-                Some(data) => Ok(data),
-                None => Err(Error::ApiError("Missing keychain data".to_owned())),
-            }
-        }
+        keychains_response.into_keychains(request)
     }
 }
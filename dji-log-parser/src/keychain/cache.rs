@@ -0,0 +1,73 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::ErrorKind;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+use super::Keychains;
+
+/// Storage of keychains retrieved from the DJI API, to avoid requesting them again.
+///
+/// Entries are keyed by `KeychainsRequest::cache_key`, so a log processed again, or a copy
+/// of it, hits the cache as long as it is requested with the same version and department.
+///
+pub trait KeychainCache {
+    /// Returns the keychains stored under `key`, if any.
+    fn get(&self, key: &str) -> Result<Option<Keychains>>;
+
+    /// Stores `keychains` under `key`.
+    fn put(&self, key: &str, keychains: &Keychains) -> Result<()>;
+}
+
+/// `KeychainCache` storing each entry in a `<key>.json` file of a directory, using the
+/// sidecar format of `Keychains::save`.
+///
+/// This cache is only available for non-WASM targets.
+///
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FsKeychainCache {
+    directory: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FsKeychainCache {
+    /// Creates a cache in `directory`, which is created on first write if missing.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        FsKeychainCache {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the path of the file holding the entry `key`.
+    pub fn entry_path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl KeychainCache for FsKeychainCache {
+    fn get(&self, key: &str) -> Result<Option<Keychains>> {
+        let path = self.entry_path(key);
+        match fs::metadata(&path) {
+            Ok(_) => Keychains::load(path).map(Some),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn put(&self, key: &str, keychains: &Keychains) -> Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        // Write then rename, so concurrent readers never see a partial entry
+        let path = self.entry_path(key);
+        let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+        keychains.save(&temporary_path)?;
+        fs::rename(temporary_path, path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::{DJILog, Error, Result};
+
+use super::{KeychainFeaturePoint, KeychainsRequest};
+
+/// Version of the sidecar file format written by `Keychains::save`.
+pub const KEYCHAINS_FORMAT: u32 = 1;
+
+/// Extension appended to the log file name to build the sidecar file name.
+const SIDECAR_EXTENSION: &str = "keychains.json";
+
+/// Keychains of a log, along with what identifies the log they belong to.
+///
+/// `Keychains` are meant to be retrieved once from the DJI API and stored along with the
+/// log, in a JSON sidecar file, for further offline use:
+///
+/// ```json
+/// {
+///   "format": 1,
+///   "fingerprint": "8c3f1b2a9d0e4f57",
+///   "version": 13,
+///   "department": 3,
+///   "keychains": [
+///     [
+///       { "featurePoint": "FR_Standardization_Feature_Base_1", "aesKey": "...", "aesIv": "..." }
+///     ]
+///   ]
+/// }
+/// ```
+///
+/// * `format` - Version of the sidecar format, currently `1`.
+/// * `fingerprint` - Fingerprint of the log, as returned by `KeychainsRequest::fingerprint`.
+/// * `version` - Keychains version sent to the DJI API.
+/// * `department` - Department id sent to the DJI API.
+/// * `keychains` - One keychain per `KeyStorageRecover` delimited section of the log, with
+///   base64 encoded AES keys and IVs.
+///
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct Keychains {
+    pub format: u32,
+    pub fingerprint: String,
+    pub version: u16,
+    pub department: u8,
+    pub keychains: Vec<Vec<KeychainFeaturePoint>>,
+}
+
+impl Keychains {
+    /// Creates `Keychains` from the request sent to the DJI API and its response.
+    pub fn new(request: &KeychainsRequest, keychains: Vec<Vec<KeychainFeaturePoint>>) -> Self {
+        Keychains {
+            format: KEYCHAINS_FORMAT,
+            fingerprint: request.fingerprint(),
+            version: request.version,
+            department: request.department,
+            keychains,
+        }
+    }
+
+    /// Fetches the keychains of `log` from the DJI API.
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - The log to retrieve keychains for.
+    /// * `api_key` - The API key for authentication with the DJI API.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch(log: &DJILog, api_key: &str) -> Result<Self> {
+        let request = log.keychains_request()?;
+        let keychains = if log.version >= 13 {
+            request.fetch(api_key, None)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Keychains::new(&request, keychains))
+    }
+
+    /// Returns the path of the sidecar file of the log at `log_path`, i.e. the log path with
+    /// `.keychains.json` appended.
+    pub fn sidecar_path(log_path: impl AsRef<Path>) -> PathBuf {
+        let mut path = log_path.as_ref().as_os_str().to_owned();
+        path.push(".");
+        path.push(SIDECAR_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Writes the keychains to a JSON sidecar file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads keychains from a JSON sidecar file.
+    ///
+    /// Returns `Error::UnsupportedKeychainsFormat` if the file was written in a later format.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let keychains: Keychains = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+
+        if keychains.format > KEYCHAINS_FORMAT {
+            return Err(Error::UnsupportedKeychainsFormat(keychains.format));
+        }
+
+        Ok(keychains)
+    }
+
+    /// Checks that the keychains belong to `log`.
+    ///
+    /// Logs prior to version 13 are not encrypted, so any keychains are accepted. Only the
+    /// `KeyStorage` records of `log` are read, and their fingerprint is kept for later checks.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::KeychainMismatch` if the fingerprint of `log` differs.
+    ///
+    pub fn check(&self, log: &DJILog) -> Result<()> {
+        if log.version < 13 {
+            return Ok(());
+        }
+
+        let fingerprint = log.keychains_fingerprint();
+        if fingerprint != self.fingerprint {
+            return Err(Error::KeychainMismatch {
+                expected: fingerprint.to_string(),
+                found: self.fingerprint.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
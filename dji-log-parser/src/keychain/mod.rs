@@ -6,10 +6,20 @@ use std::collections::HashMap;
 use tsify_next::Tsify;
 
 mod api;
+mod cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod client;
 mod feature_point;
+mod keychains;
 
 pub use api::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::FsKeychainCache;
+pub use cache::KeychainCache;
+#[cfg(not(target_arch = "wasm32"))]
+pub use client::{KeychainClient, KeychainClientBuilder};
 pub use feature_point::FeaturePoint;
+pub use keychains::{Keychains, KEYCHAINS_FORMAT};
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +42,7 @@ pub struct KeychainFeaturePoint {
 /// It associates each `FeaturePoint` with its corresponding AES initialization vector (IV)
 /// and encryption key. In this hashmap, each `FeaturePoint` is linked to a tuple containing
 /// the AES IV and key as array of bytes.
+#[derive(Clone)]
 pub(crate) struct Keychain(HashMap<FeaturePoint, (Vec<u8>, Vec<u8>)>);
 
 impl Keychain {
@@ -1,16 +1,19 @@
-use binrw::binread;
+use binrw::{binread, BinRead, BinResult};
 use serde::Serialize;
+use std::io::{Read, Seek, SeekFrom};
 
-use crate::decoder::XorDecoder;
+use crate::decoder::xor_decoder;
 
+/// Block located between the `Prefix` and the records in logs versions >= 13.
 #[binread]
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", content = "content")]
 #[br(little)]
-pub(crate) enum Auxiliary {
+pub enum Auxiliary {
     #[br(magic = 0u8)]
     Info(
         #[br(temp)] u16,
-        #[br(pad_size_to = self_0, map_stream = |reader| XorDecoder::new(reader, 0))] AuxiliaryInfo,
+        #[br(pad_size_to = self_0, map_stream = |reader| xor_decoder(reader, 0))] AuxiliaryInfo,
     ),
 
     #[br(magic = 1u8)]
@@ -18,15 +21,21 @@ pub(crate) enum Auxiliary {
         #[br(temp)] u16,
         #[br(pad_size_to = self_0)] AuxiliaryVersion,
     ),
+
+    /// Block of an unknown type, kept as raw bytes
+    Unknown(u8, #[br(temp)] u16, #[br(count = self_1)] Vec<u8>),
 }
 
+/// Block holding the log `Details` along with their signature.
 #[binread]
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 #[br(little)]
-pub(crate) struct AuxiliaryInfo {
+pub struct AuxiliaryInfo {
     pub version_data: u8,
     #[br(temp)]
     info_length: u16,
+    /// Raw `Details` block
     #[br(count = info_length)]
     pub info_data: Vec<u8>,
     #[br(temp)]
@@ -35,15 +44,51 @@ pub(crate) struct AuxiliaryInfo {
     pub signature_data: Vec<u8>,
 }
 
+/// Block holding the version and department used to request keychains.
 #[binread]
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 #[br(little)]
-pub(crate) struct AuxiliaryVersion {
+pub struct AuxiliaryVersion {
     pub version: u16,
     #[br(map = |x: u8| Department::from(x))]
     pub department: Department,
 }
 
+/// Reads the auxiliary blocks starting at `start_offset`.
+///
+/// Blocks are read up to `end_offset`, the start of the records. When the records offset is
+/// unknown, blocks are read up to the `Version` block included.
+///
+/// Returns the blocks along with the offset following the last one.
+///
+pub(crate) fn read_auxiliary_blocks<R: Read + Seek>(
+    reader: &mut R,
+    start_offset: u64,
+    end_offset: Option<u64>,
+) -> BinResult<(Vec<Auxiliary>, u64)> {
+    let mut blocks = Vec::new();
+    let mut position = reader.seek(SeekFrom::Start(start_offset))?;
+
+    loop {
+        if end_offset.is_some_and(|end_offset| position >= end_offset) {
+            break;
+        }
+
+        let block = Auxiliary::read(reader)?;
+        position = reader.stream_position()?;
+
+        let is_version = matches!(block, Auxiliary::Version(_));
+        blocks.push(block);
+
+        if end_offset.is_none() && is_version {
+            break;
+        }
+    }
+
+    Ok((blocks, position))
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub enum Department {
     SDK,
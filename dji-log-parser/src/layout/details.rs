@@ -229,6 +229,74 @@ impl From<u8> for ProductType {
     }
 }
 
+impl From<ProductType> for u8 {
+    fn from(product_type: ProductType) -> Self {
+        match product_type {
+            ProductType::None => 0,
+            ProductType::Inspire1 => 1,
+            ProductType::Phantom3Standard => 2,
+            ProductType::Phantom3Advanced => 3,
+            ProductType::Phantom3Pro => 4,
+            ProductType::OSMO => 5,
+            ProductType::Matrice100 => 6,
+            ProductType::Phantom4 => 7,
+            ProductType::LB2 => 8,
+            ProductType::Inspire1Pro => 9,
+            ProductType::A3 => 10,
+            ProductType::Matrice600 => 11,
+            ProductType::Phantom34K => 12,
+            ProductType::MavicPro => 13,
+            ProductType::ZenmuseXT => 14,
+            ProductType::Inspire1RAW => 15,
+            ProductType::A2 => 16,
+            ProductType::Inspire2 => 17,
+            ProductType::OSMOPro => 18,
+            ProductType::OSMORaw => 19,
+            ProductType::OSMOPlus => 20,
+            ProductType::Mavic => 21,
+            ProductType::OSMOMobile => 22,
+            ProductType::OrangeCV600 => 23,
+            ProductType::Phantom4Pro => 24,
+            ProductType::N3FC => 25,
+            ProductType::Spark => 26,
+            ProductType::Matrice600Pro => 27,
+            ProductType::Phantom4Advanced => 28,
+            ProductType::Phantom3SE => 29,
+            ProductType::AG405 => 30,
+            ProductType::Matrice200 => 31,
+            ProductType::Matrice210 => 33,
+            ProductType::Matrice210RTK => 34,
+            ProductType::MavicAir => 38,
+            ProductType::Mavic2 => 42,
+            ProductType::Phantom4ProV2 => 44,
+            ProductType::Phantom4RTK => 46,
+            ProductType::Phantom4Multispectral => 57,
+            ProductType::Mavic2Enterprise => 58,
+            ProductType::MavicMini => 59,
+            ProductType::Matrice200V2 => 60,
+            ProductType::Matrice210V2 => 61,
+            ProductType::Matrice210RTKV2 => 62,
+            ProductType::MavicAir2 => 67,
+            ProductType::Matrice300RTK => 70,
+            ProductType::FPV => 73,
+            ProductType::MavicAir2S => 75,
+            ProductType::Mini2 => 76,
+            ProductType::Mavic3 => 77,
+            ProductType::MiniSE => 96,
+            ProductType::Mini3Pro => 103,
+            ProductType::Mavic3Pro => 111,
+            ProductType::Mini2SE => 113,
+            ProductType::Matrice30 => 116,
+            ProductType::Mavic3Enterprise => 118,
+            ProductType::Avata => 121,
+            ProductType::Mini4Pro => 126,
+            ProductType::Avata2 => 152,
+            ProductType::Matrice350RTK => 170,
+            ProductType::Unknown(num) => num,
+        }
+    }
+}
+
 impl ProductType {
     pub fn battery_cell_num(&self) -> u8 {
         match self {
@@ -364,6 +432,20 @@ impl From<u8> for Platform {
     }
 }
 
+impl From<Platform> for u8 {
+    fn from(platform: Platform) -> Self {
+        match platform {
+            Platform::IOS => 1,
+            Platform::Android => 2,
+            Platform::DJIFly => 6,
+            Platform::Windows => 10,
+            Platform::Mac => 11,
+            Platform::Linux => 12,
+            Platform::Unknown(num) => num,
+        }
+    }
+}
+
 /// Decode the battery serial number from raw bytes, choosing the method based on model:
 /// - For Inspire1/Pro/RAW, interpret the low nibble of each byte as a BCD digit,
 ///   reverse the sequence, and trim leading `'0'`.
@@ -379,7 +461,9 @@ pub fn parse_battery_sn(product_type: ProductType, buf: Vec<u8>) -> String {
     if BCD_PRODUCTS.contains(&product_type) {
         decode_reversed_bcd_battery_sn(buf)
     } else {
-        String::from_utf8_lossy(&buf).trim_end_matches('\0').to_string()
+        String::from_utf8_lossy(&buf)
+            .trim_end_matches('\0')
+            .to_string()
     }
 }
 
@@ -2,7 +2,9 @@ use binrw::binread;
 
 // Constants
 const OLD_PREFIX_SIZE: u64 = 12;
-const PREFIX_SIZE: u64 = 100;
+pub(crate) const PREFIX_SIZE: u64 = 100;
+/// Size of the Details block in versions prior to 13.
+pub(crate) const INFO_SIZE: u64 = 436;
 
 #[binread]
 #[derive(Debug, Clone)]
@@ -38,17 +40,19 @@ impl Prefix {
         } else if self.version < 12 {
             PREFIX_SIZE
         } else if self.version == 12 {
-            PREFIX_SIZE + 436 // We manually add info size
+            PREFIX_SIZE + INFO_SIZE // We manually add info size
         } else {
             self.detail_offset
         }
     }
 
     pub(crate) fn records_end_offset(&self, file_size: impl Into<u64>) -> u64 {
+        let file_size = file_size.into();
         if self.version < 12 {
-            self.detail_offset
+            // Corrupted prefixes can point past the end of the file
+            self.detail_offset.min(file_size)
         } else {
-            file_size.into()
+            file_size
         }
     }
 }
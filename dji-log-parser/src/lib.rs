@@ -27,6 +27,13 @@
 //! let parser = DJILog::from_bytes(bytes).unwrap();
 //! ```
 //!
+//! Large logs can be opened from a file path or any `Read + Seek` source instead. Records are then
+//! read on demand and the file is never fully loaded in memory:
+//!
+//! ```ignore
+//! let parser = DJILog::open("DJIFlightRecord.txt").unwrap();
+//! ```
+//!
 //! ### Access general data
 //!
 //! General data are not encrypted and can be accessed from the parser for all log versions:
@@ -48,7 +55,16 @@
 //! let keychains = parser.fetch_keychains("__DJI_API_KEY__").unwrap();
 //! ```
 //!
-//! Keychains can be retrieved once, serialized, and stored along with the log file for future offline use.
+//! Keychains can be retrieved once and stored along with the log file, in a JSON sidecar file,
+//! for future offline use. `Keychains` keep the fingerprint of the log they belong to:
+//!
+//! ```ignore
+//! let keychains = Keychains::fetch(&parser, "__DJI_API_KEY__")?;
+//! keychains.save(Keychains::sidecar_path("DJIFlightRecord.txt"))?;
+//!
+//! let keychains = Keychains::load(Keychains::sidecar_path("DJIFlightRecord.txt"))?;
+//! let records = parser.records_with(&keychains)?;
+//! ```
 //!
 //! ### Accessing Frames
 //!
@@ -84,6 +100,16 @@
 //! let records = parser.records(Some(keychains));
 //! ```
 //!
+//! ### Streaming Records and Frames
+//!
+//! For long logs, records and frames can be decoded lazily to keep memory usage low:
+//!
+//! ```ignore
+//! for frame in parser.frames_iter(Some(keychains))? {
+//!     println!("{}", frame.osd.latitude);
+//! }
+//! ```
+//!
 //!
 //! ## Binary structure of log files:
 //!
@@ -140,37 +166,84 @@
 //! ```
 use base64::engine::general_purpose::STANDARD as Base64Standard;
 use base64::Engine as _;
-use binrw::io::Cursor;
+use binrw::io::{BufReader, Cursor};
 use binrw::BinRead;
-use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
 
 mod decoder;
+mod encoder;
 mod error;
+mod filter;
+mod flight;
+mod follower;
 pub mod frame;
+mod iter;
 pub mod keychain;
 pub mod layout;
+#[cfg(feature = "experimental-records")]
+mod mission;
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+mod parallel;
+mod probe;
 pub mod record;
+mod redact;
+mod report;
+#[cfg(feature = "experimental-records")]
+mod traffic;
 mod utils;
+mod writer;
 
 pub use error::{Error, Result};
-use frame::{records_to_frames, Frame};
-use keychain::{EncodedKeychainFeaturePoint, Keychain, KeychainFeaturePoint, KeychainsRequest};
-use layout::auxiliary::{Auxiliary, Department};
+pub use filter::RecordFilter;
+pub use flight::{Flight, FlightSummary};
+pub use follower::{DJILogFollower, FollowerUpdate};
+use frame::{frames_from_records, Frame, FrameIter};
+pub use iter::{RecordEnvelopeIter, RecordIter};
+use keychain::{
+    EncodedKeychainFeaturePoint, Keychain, KeychainFeaturePoint, Keychains, KeychainsRequest,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use keychain::{KeychainCache, KeychainClient};
+use layout::auxiliary::{read_auxiliary_blocks, Auxiliary, AuxiliaryVersion, Department};
 use layout::details::Details;
-use layout::prefix::Prefix;
-use record::Record;
+use layout::prefix::{Prefix, INFO_SIZE};
+#[cfg(feature = "experimental-records")]
+pub use mission::Mission;
+pub use probe::{probe, ProbeResult};
+use record::{Record, RecordEnvelope, KEY_STORAGE_RECOVER_TYPE_ID, KEY_STORAGE_TYPE_ID};
+pub use redact::{Redaction, SerialRedaction};
+pub use report::{DecryptionStats, ParseFailure, ParseReport, SkippedRegion};
+#[cfg(feature = "experimental-records")]
+pub use traffic::{ClosestApproach, TrafficEncounter};
+pub use writer::DJILogWriter;
 
+use crate::decoder::SeekRead;
 use crate::utils::pad_with_zeros;
 
-#[derive(Debug)]
 pub struct DJILog {
-    inner: Vec<u8>,
+    reader: Mutex<Box<dyn SeekRead + Send>>,
+    size: u64,
     prefix: Prefix,
     /// Log format version
     pub version: u8,
     /// Log Details. Contains record summary and general informations
     pub details: Details,
+    fingerprint: OnceLock<String>,
+}
+
+impl fmt::Debug for DJILog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DJILog")
+            .field("size", &self.size)
+            .field("prefix", &self.prefix)
+            .field("version", &self.version)
+            .field("details", &self.details)
+            .finish_non_exhaustive()
+    }
 }
 
 impl DJILog {
@@ -198,41 +271,133 @@ impl DJILog {
     /// ```
     ///
     pub fn from_bytes(bytes: Vec<u8>) -> Result<DJILog> {
+        Self::from_reader(Cursor::new(bytes))
+    }
+
+    /// Constructs a `DJILog` from any seekable reader.
+    ///
+    /// Only the Prefix and Info blocks are read at construction. Records are read
+    /// from the reader on demand, so the log file is never fully loaded in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader implementing `Read` and `Seek`, positioned anywhere in the DJI log file.
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<DJILog>`.
+    /// On success, it returns the `DJILog` instance.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use djilog_parser::DJILog;
+    ///
+    /// let file = std::fs::File::open("path/to/log/file").unwrap();
+    /// let log = DJILog::from_reader(binrw::io::BufReader::new(file)).unwrap();
+    /// ```
+    ///
+    pub fn from_reader<R>(mut reader: R) -> Result<DJILog>
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        let size = reader.seek(SeekFrom::End(0))?;
+        reader.rewind()?;
+
         // Decode Prefix
-        let mut prefix = Prefix::read(&mut Cursor::new(&bytes))?;
+        let mut prefix = Prefix::read(&mut reader)
+            .map_err(|_| Error::NotDJILog("file shorter than the prefix".into()))?;
 
         let version = prefix.version;
 
         // Decode Detail
-        let detail_offset = prefix.detail_offset() as usize;
-        let mut cursor = Cursor::new(pad_with_zeros(&bytes[detail_offset..], 400));
+        let detail_offset = prefix.detail_offset();
+        reader.seek(SeekFrom::Start(detail_offset))?;
 
         let details = if version < 13 {
+            let mut buffer = Vec::new();
+            (&mut reader).take(INFO_SIZE).read_to_end(&mut buffer)?;
+            let mut cursor = Cursor::new(pad_with_zeros(&buffer, 400));
             Details::read_args(&mut cursor, (version,))?
         } else {
-            // Get details from first auxiliary block
-            if let Auxiliary::Info(data) = Auxiliary::read(&mut cursor)? {
-                Details::read_args(&mut Cursor::new(&data.info_data), (version,))?
-            } else {
-                return Err(Error::MissingAuxilliaryData("Info".into()));
+            // Records follow the auxiliary blocks, their offset is unknown if not set
+            let records_offset = Some(prefix.records_offset()).filter(|offset| *offset != 0);
+            let (blocks, end_offset) =
+                read_auxiliary_blocks(&mut reader, detail_offset, records_offset)?;
+
+            // Try to recover detail offset
+            if records_offset.is_none() {
+                prefix.recover_detail_offset(end_offset);
             }
-        };
 
-        // Try to recover detail offset
-        if prefix.records_offset() == 0 && version >= 13 {
-            // Skip second auxiliary block
-            let _ = Auxiliary::read(&mut cursor)?;
-            prefix.recover_detail_offset(cursor.position() + detail_offset as u64);
-        }
+            // Get details from info auxiliary block
+            match blocks
+                .iter()
+                .find(|block| matches!(block, Auxiliary::Info(_)))
+            {
+                Some(Auxiliary::Info(data)) => {
+                    Details::read_args(&mut Cursor::new(&data.info_data), (version,))?
+                }
+                _ => return Err(Error::MissingAuxilliaryData("Info".into())),
+            }
+        };
 
         Ok(DJILog {
-            inner: bytes,
+            reader: Mutex::new(Box::new(reader)),
+            size,
             prefix,
             version,
             details,
+            fingerprint: OnceLock::new(),
         })
     }
 
+    /// Constructs a `DJILog` from a file path.
+    ///
+    /// The file is read through a buffered reader: only the Prefix and Info blocks
+    /// are read at construction, records are read on demand.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the DJI log file.
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<DJILog>`.
+    /// On success, it returns the `DJILog` instance.
+    ///
+    pub fn open(path: impl AsRef<Path>) -> Result<DJILog> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Constructs a `DJILog` from a memory-mapped file.
+    /// Available behind the `mmap` feature.
+    ///
+    /// Pages of the file are loaded by the operating system as records are read,
+    /// which avoids both copying the file and issuing read syscalls.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the DJI log file.
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<DJILog>`.
+    /// On success, it returns the `DJILog` instance.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the `DJILog` is alive.
+    /// See `memmap2::Mmap::map` for details.
+    ///
+    #[cfg(all(feature = "mmap", not(target_arch = "wasm32")))]
+    pub unsafe fn open_mmap(path: impl AsRef<Path>) -> Result<DJILog> {
+        let file = File::open(path)?;
+        let mmap = memmap2::Mmap::map(&file)?;
+        Self::from_reader(Cursor::new(mmap))
+    }
+
     /// Creates a `KeychainsRequest` object by parsing `KeyStorage` records.
     ///
     /// This function is used to build a request body for manually retrieving the keychain from the DJI API.
@@ -265,79 +430,147 @@ impl DJILog {
     ///
     /// Returns a `Result<KeychainsRequest>`. On success, it provides a `KeychainsRequest`
     /// instance, which contains the necessary information to fetch keychains from the DJI API.
+    /// Returns `Error::IncompleteKeyStorage` if a `KeyStorage` or `KeyStorageRecover` record cannot
+    /// be decoded or is cut off, as its keychain would be missing. Other undecodable records, such
+    /// as a truncated trailing record of a crashed flight, do not fail the request. Use
+    /// `keychains_request_with_report` to get the request anyway.
     ///
     pub fn keychains_request_with_custom_params(
         &self,
         department: Option<Department>,
         version: Option<u16>,
     ) -> Result<KeychainsRequest> {
+        let (keychain_request, report) = self.keychains_request_with_report(department, version)?;
+
+        // Only a broken KeyStorage record loses a keychain entry
+        if let Some(failure) = report.failures.into_iter().find(|failure| {
+            matches!(
+                failure.record_type,
+                Some(KEY_STORAGE_TYPE_ID | KEY_STORAGE_RECOVER_TYPE_ID)
+            )
+        }) {
+            return Err(Error::IncompleteKeyStorage {
+                offset: failure.offset,
+                error: Box::new(failure.error),
+            });
+        }
+
+        Ok(keychain_request)
+    }
+
+    /// Creates a `KeychainsRequest` object by parsing `KeyStorage` records, along with the
+    /// `ParseReport` of the records decoding pass.
+    ///
+    /// Unlike `keychains_request_with_custom_params`, records that cannot be decoded do not fail
+    /// the request. The request then only holds the keychains found before them, which the report
+    /// tells. `KeyStorage` records decoded as `Record::Invalid` because they are cut off are also
+    /// listed in the report failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `department` - An optional `Department` to manually set in the request. If `None`, the department
+    ///   will be determined from the log file.
+    /// * `version` - An optional version number to manually set in the request. If `None`, the version
+    ///   will be determined from the log file.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<(KeychainsRequest, ParseReport)>`. On success, it provides the
+    /// `KeychainsRequest` instance and the report of the decoding pass.
+    ///
+    pub fn keychains_request_with_report(
+        &self,
+        department: Option<Department>,
+        version: Option<u16>,
+    ) -> Result<(KeychainsRequest, ParseReport)> {
         let mut keychain_request = KeychainsRequest::default();
 
         // No keychain
         if self.version < 13 {
-            return Ok(keychain_request);
+            return Ok((keychain_request, ParseReport::default()));
         }
 
-        let mut cursor = Cursor::new(&self.inner);
-        cursor.set_position(self.prefix.detail_offset());
-
-        // Skip first auxiliary block
-        let _ = Auxiliary::read(&mut cursor)?;
-
         // Get version from second auxilliary block
-        if let Auxiliary::Version(data) = Auxiliary::read(&mut cursor)? {
-            // Use provided version or determine from log
-            keychain_request.version = version.unwrap_or(data.version);
-            // Use provided department or determine from log
-            keychain_request.department = match department {
-                Some(dept) => dept.into(),
-                None => match data.department {
-                    Department::Unknown(_) => Department::DJIFly.into(),
-                    _ => data.department.into(),
-                },
-            };
-        } else {
-            return Err(Error::MissingAuxilliaryData("Version".into()));
-        }
+        let data = self.auxiliary_version()?;
+        // Use provided version or determine from log
+        keychain_request.version = version.unwrap_or(data.version);
+        // Use provided department or determine from log
+        keychain_request.department = match department {
+            Some(dept) => dept.into(),
+            None => match data.department {
+                Department::Unknown(_) => Department::DJIFly.into(),
+                _ => data.department.into(),
+            },
+        };
 
         // Extract keychains from KeyStorage Records
-        cursor.set_position(self.prefix.records_offset());
-
-        let mut keychain: Vec<EncodedKeychainFeaturePoint> = Vec::new();
-
-        while cursor.position() < self.prefix.records_end_offset(self.inner.len() as u64) {
-            let empty_keychain = &RefCell::new(Keychain::empty());
-            let record = match Record::read_args(
-                &mut cursor,
-                binrw::args! {
-                    version: self.version,
-                    keychain: empty_keychain
-                },
-            ) {
-                Ok(record) => record,
-                Err(_) => break,
-            };
-
-            match record {
-                Record::KeyStorage(data) => {
-                    // add EncodedKeychainFeaturePoint to current keychain
-                    keychain.push(EncodedKeychainFeaturePoint {
-                        feature_point: data.feature_point,
-                        aes_ciphertext: Base64Standard.encode(&data.data),
-                    });
-                }
-                Record::KeyStorageRecover(_) => {
-                    // start a new keychain
-                    keychain_request.keychains.push(keychain);
-                    keychain = Vec::new();
-                }
-                _ => {}
+        let mut envelopes = RecordIter::new(self, Vec::new()).envelopes();
+        let (keychains, cut_key_storage) = key_storage_keychains(&mut envelopes);
+        keychain_request.keychains = keychains;
+
+        let mut report = envelopes.into_report();
+        report.failures.extend(cut_key_storage);
+
+        Ok((keychain_request, report))
+    }
+
+    /// Returns the fingerprint of the `KeyStorage` records of the log, as returned by
+    /// `KeychainsRequest::fingerprint`.
+    ///
+    /// Only `KeyStorage` records are decoded, others are skipped using their length prefix and
+    /// undecodable ones are stepped over, so the fingerprint does not depend on the rest of the
+    /// log. It is computed on the first call only.
+    ///
+    pub(crate) fn keychains_fingerprint(&self) -> &str {
+        self.fingerprint.get_or_init(|| {
+            let mut envelopes = RecordIter::new(self, Vec::new())
+                .with_filter(RecordFilter::new([KEY_STORAGE_TYPE_ID]))
+                .with_recovery()
+                .envelopes();
+            let (keychains, _) = key_storage_keychains(&mut envelopes);
+
+            KeychainsRequest {
+                keychains,
+                ..Default::default()
             }
+            .fingerprint()
+        })
+    }
+
+    /// Reads the `Auxiliary` Version block (versions >= 13).
+    pub(crate) fn auxiliary_version(&self) -> Result<AuxiliaryVersion> {
+        self.auxiliary_blocks()?
+            .into_iter()
+            .find_map(|block| match block {
+                Auxiliary::Version(data) => Some(data),
+                _ => None,
+            })
+            .ok_or_else(|| Error::MissingAuxilliaryData("Version".into()))
+    }
+
+    /// Retrieves the auxiliary blocks located between the prefix and the records.
+    ///
+    /// Auxiliary blocks are only present in logs versions >= 13. They hold the details and their
+    /// signature, and the version and department used to request keychains. Blocks of unknown
+    /// types are returned as `Auxiliary::Unknown`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Auxiliary>>`, in file order. The vector is empty for logs versions < 13.
+    ///
+    pub fn auxiliary_blocks(&self) -> Result<Vec<Auxiliary>> {
+        if self.version < 13 {
+            return Ok(Vec::new());
         }
 
-        keychain_request.keychains.push(keychain);
+        let mut reader = self.reader();
+        let (blocks, _) = read_auxiliary_blocks(
+            &mut *reader,
+            self.prefix.detail_offset(),
+            Some(self.prefix.records_offset()),
+        )?;
 
-        Ok(keychain_request)
+        Ok(blocks)
     }
 
     /// Fetches keychains using the provided API key.
@@ -364,6 +597,61 @@ impl DJILog {
         }
     }
 
+    /// Fetches keychains using the provided API key and `KeychainClient`.
+    ///
+    /// The client sets the endpoint, timeout, retries, proxy and user agent of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The `KeychainClient` sending the request.
+    /// * `api_key` - A string slice that holds the API key for authentication with the DJI API.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Vec<KeychainFeaturePoint>>>`. On success, it provides a vector of vectors,
+    /// where each inner vector represents a keychain.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch_keychains_with_client(
+        &self,
+        client: &KeychainClient,
+        api_key: &str,
+    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
+        if self.version >= 13 {
+            client.fetch(api_key, &self.keychains_request()?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Fetches keychains using the provided API key, unless they are found in `cache`.
+    ///
+    /// Fetched keychains are stored in `cache`, so the DJI API is only requested once per log.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - A string slice that holds the API key for authentication with the DJI API.
+    /// * `cache` - The `KeychainCache` to look up first, e.g. a `FsKeychainCache`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Vec<KeychainFeaturePoint>>>`. On success, it provides a vector of vectors,
+    /// where each inner vector represents a keychain.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fetch_keychains_with_cache(
+        &self,
+        api_key: &str,
+        cache: &dyn KeychainCache,
+    ) -> Result<Vec<Vec<KeychainFeaturePoint>>> {
+        if self.version >= 13 {
+            self.keychains_request()?
+                .fetch_with_cache(api_key, None, cache)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     /// Fetches keychains asynchronously using the provided API key.
     /// Available on wasm and native behind the `native-async` feature.
     ///
@@ -412,46 +700,213 @@ impl DJILog {
         &self,
         keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
     ) -> Result<Vec<Record>> {
+        Ok(self.records_iter(keychains)?.collect())
+    }
+
+    /// Retrieves the parsed raw records from the DJI log, decoding them on all available cores.
+    /// Available behind the `parallel` feature.
+    ///
+    /// Record boundaries and AES IV chains are first scanned sequentially, then records are
+    /// decoded in parallel. Records are returned in log order, exactly as with `records`.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Record>>`. On success, it provides a vector of `Record`
+    /// instances representing the parsed log records.
+    ///
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn records_parallel(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<Record>> {
+        Ok(self.records_parallel_with_report(keychains)?.0)
+    }
+
+    /// Retrieves the parsed raw records from the DJI log along with a `ParseReport`, decoding
+    /// them on all available cores. Available behind the `parallel` feature.
+    ///
+    /// See `records_parallel` and `records_with_report`. The report is the same as the one of
+    /// `records_with_report`, apart from skipped regions as there is no recovery mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<(Vec<Record>, ParseReport)>`. On success, it provides a vector of `Record`
+    /// instances representing the parsed log records and the report of the decoding pass.
+    ///
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn records_parallel_with_report(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<(Vec<Record>, ParseReport)> {
+        let (envelopes, report) =
+            parallel::decode_envelopes(self, self.decryption_keychains(keychains)?)?;
+        let records = envelopes
+            .into_iter()
+            .map(|envelope| envelope.record)
+            .collect();
+        Ok((records, report))
+    }
+
+    /// Retrieves the normalized frames from the DJI log, decoding records on all available cores.
+    /// Available behind the `parallel` feature.
+    ///
+    /// See `records_parallel` and `frames`.
+    ///
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn frames_parallel(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<Frame>> {
+        let (envelopes, _) =
+            parallel::decode_envelopes(self, self.decryption_keychains(keychains)?)?;
+        Ok(frames_from_records(&envelopes, self.details.clone()))
+    }
+
+    /// Retrieves the parsed raw records accepted by `filter` from the DJI log.
+    ///
+    /// Records rejected by the filter are skipped using their length prefix, without being
+    /// decrypted nor parsed, which is much faster when only a few record types are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    /// * `filter` - The set of record types to decode.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Record>>`. On success, it provides a vector of the `Record`
+    /// instances accepted by the filter, in log order.
+    ///
+    pub fn records_with_filter(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+        filter: RecordFilter,
+    ) -> Result<Vec<Record>> {
+        Ok(self.records_iter(keychains)?.with_filter(filter).collect())
+    }
+
+    /// Retrieves the parsed raw records from the DJI log along with a `ParseReport`.
+    ///
+    /// Decoding stops at the first record that cannot be decoded. The report tells where
+    /// and why it stopped, how many bytes were left unparsed and how many `Unknown`
+    /// and `Invalid` records were decoded. Records that cannot be decrypted do not stop
+    /// decoding: they are returned as `Record::Invalid` and listed in the report failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<(Vec<Record>, ParseReport)>`. On success, it provides a vector of `Record`
+    /// instances representing the parsed log records and the report of the decoding pass.
+    ///
+    pub fn records_with_report(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<(Vec<Record>, ParseReport)> {
+        let mut iter = self.records_iter(keychains)?;
+        let records = iter.by_ref().collect();
+        Ok((records, iter.into_report()))
+    }
+
+    /// Retrieves the parsed raw records from the DJI log, decrypted with keychains stored along
+    /// with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - The `Keychains` of the log, as fetched with `Keychains::fetch` or loaded
+    ///   from a sidecar file with `Keychains::load`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Record>>`. Returns `Error::KeychainMismatch` if the keychains
+    /// belong to another log.
+    ///
+    pub fn records_with(&self, keychains: &Keychains) -> Result<Vec<Record>> {
+        keychains.check(self)?;
+        self.records(Some(keychains.keychains.clone()))
+    }
+
+    /// Retrieves the parsed raw records from the DJI log wrapped in a `RecordEnvelope`.
+    ///
+    /// Envelopes expose the byte offset, length, raw type id and decryption feature point
+    /// of each record along with the parsed record.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<RecordEnvelope>>`. On success, it provides a vector of `RecordEnvelope`
+    /// instances in log order.
+    ///
+    pub fn record_envelopes(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<RecordEnvelope>> {
+        Ok(self.records_iter(keychains)?.envelopes().collect())
+    }
+
+    /// Returns a lazy iterator over the parsed raw records from the DJI log.
+    ///
+    /// Unlike `records`, records are decoded on demand, one at a time, so the whole
+    /// list of records is never held in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<RecordIter>`. On success, it provides an iterator yielding `Record`
+    /// instances in log order.
+    ///
+    pub fn records_iter(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<RecordIter<'_>> {
+        Ok(RecordIter::new(self, self.decryption_keychains(keychains)?))
+    }
+
+    /// Builds the keychains used to decrypt records, checking they are provided for
+    /// encrypted logs.
+    pub(crate) fn decryption_keychains(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<Keychain>> {
         if self.version >= 13 && keychains.is_none() {
             return Err(Error::KeychainRequired);
         }
 
-        let mut keychains = VecDeque::from(match keychains {
+        Ok(match keychains {
             Some(keychains) => keychains
                 .iter()
                 .map(Keychain::from_feature_points)
                 .collect(),
             None => Vec::new(),
-        });
-
-        let mut cursor = Cursor::new(&self.inner);
-        cursor.set_position(self.prefix.records_offset());
-
-        let mut keychain = RefCell::new(keychains.pop_front().unwrap_or(Keychain::empty()));
-
-        let mut records = Vec::new();
-
-        while cursor.position() < self.prefix.records_end_offset(self.inner.len() as u64) {
-            // decode record
-            let record = match Record::read_args(
-                &mut cursor,
-                binrw::args! {
-                    version: self.version,
-                    keychain: &keychain
-                },
-            ) {
-                Ok(record) => record,
-                Err(_) => break,
-            };
-
-            if let Record::KeyStorageRecover(_) = record {
-                keychain = RefCell::new(keychains.pop_front().unwrap_or(Keychain::empty()));
-            }
-
-            records.push(record);
-        }
-
-        Ok(records)
+        })
     }
 
     /// Retrieves the normalized frames from the DJI log.
@@ -483,7 +938,272 @@ impl DJILog {
     /// versions, simplifying data analysis and interpretation.
     ///
     pub fn frames(&self, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) -> Result<Vec<Frame>> {
-        let records = self.records(keychains)?;
-        Ok(records_to_frames(records, self.details.clone()))
+        Ok(self.frames_iter(keychains)?.collect())
     }
+
+    /// Retrieves the normalized frames from the DJI log, decrypted with keychains stored along
+    /// with it.
+    ///
+    /// See `records_with` and `frames`.
+    ///
+    pub fn frames_with(&self, keychains: &Keychains) -> Result<Vec<Frame>> {
+        keychains.check(self)?;
+        self.frames(Some(keychains.keychains.clone()))
+    }
+
+    /// Splits the DJI log into individual flights.
+    ///
+    /// A log can hold several takeoffs and landings. Each `Flight` gives the range of its frames
+    /// within the frames returned by `frames`, along with its times, takeoff and landing
+    /// positions and summary statistics. Use `Flight::split` when frames are already available.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Flight>>`. On success, it provides the flights in log order.
+    ///
+    pub fn flights(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<Flight>> {
+        let frames = self.frames(keychains)?;
+        Ok(Flight::split(&frames))
+    }
+
+    /// Retrieves the manned aircraft traffic detected by AirSense during the log.
+    ///
+    /// Each `TrafficEncounter` gives the frames an aircraft was detected in, within the frames
+    /// returned by `frames`, along with its closest approach to the drone. Use
+    /// `TrafficEncounter::from_frames` when frames are already available.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<TrafficEncounter>>`. On success, it provides one encounter per aircraft,
+    /// closest first.
+    ///
+    #[cfg(feature = "experimental-records")]
+    pub fn traffic(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Vec<TrafficEncounter>> {
+        let frames = self.frames(keychains)?;
+        Ok(TrafficEncounter::from_frames(&frames))
+    }
+
+    /// Retrieves the waypoint mission planned in the DJI log.
+    ///
+    /// Only the mission records are decoded. Use `Mission::from_records` when records are
+    /// already available.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Option<Mission>>`. On success, it provides the last mission uploaded to or
+    /// downloaded from the aircraft, if any, with its waypoints and their actions.
+    ///
+    #[cfg(feature = "experimental-records")]
+    pub fn mission(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Option<Mission>> {
+        let filter = RecordFilter::from_names([
+            "WaypointMissionUpload",
+            "WaypointUpload",
+            "WaypointMissionDownload",
+            "WaypointDownload",
+        ])
+        .expect("mission record names are known");
+        let records = self.records_with_filter(keychains, filter)?;
+        Ok(Mission::from_records(&records))
+    }
+
+    /// Retrieves the normalized frames from the DJI log, built only from the records
+    /// accepted by `filter`.
+    ///
+    /// Use `RecordFilter::frames` to skip the records not used to build frames. Frame
+    /// sections fed by rejected records are left to their default values.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    /// * `filter` - The set of record types to decode.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<Vec<Frame>>`. On success, it provides a vector of `Frame`
+    /// instances representing the normalized log data.
+    ///
+    pub fn frames_with_filter(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+        filter: RecordFilter,
+    ) -> Result<Vec<Frame>> {
+        let envelopes = self
+            .records_iter(keychains)?
+            .with_filter(filter)
+            .envelopes();
+        Ok(FrameIter::new(envelopes, self.details.clone()).collect())
+    }
+
+    /// Retrieves both the parsed raw records and the normalized frames from the DJI log.
+    ///
+    /// Records are decoded and decrypted only once, then converted into frames. This is
+    /// preferred over calling `records` and `frames` in turn when both are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<(Vec<Record>, Vec<Frame>)>`. On success, it provides the records and
+    /// the frames built from them, both in log order.
+    ///
+    pub fn records_and_frames(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<(Vec<Record>, Vec<Frame>)> {
+        let envelopes = self.record_envelopes(keychains)?;
+        let frames = frames_from_records(&envelopes, self.details.clone());
+        let records = envelopes
+            .into_iter()
+            .map(|envelope| envelope.record)
+            .collect();
+        Ok((records, frames))
+    }
+
+    /// Writes an anonymized copy of the DJI log, to be shared without revealing the flight
+    /// location or the serial numbers of the equipment.
+    ///
+    /// Records are decoded, redacted as described by `Redaction`, then encoded again with the
+    /// same keychains. The copy can be read as any other log, using the same keychains as the
+    /// original one for versions >= 13.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption and encryption when working with encrypted logs (versions >= 13).
+    /// * `redaction` - The redaction to apply.
+    /// * `writer` - Destination of the redacted log.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<W>`. On success, it returns the writer. Returns
+    /// `Error::IncompleteRecords` if a record stops decoding before the end of the log.
+    ///
+    pub fn redact<W: Write + Seek>(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+        redaction: &Redaction,
+        writer: W,
+    ) -> Result<W> {
+        redact::redact_log(self, keychains, redaction, writer)
+    }
+
+    /// Returns a lazy iterator over the normalized frames from the DJI log.
+    ///
+    /// Records are decoded and normalized on demand, so only the frame being built is kept
+    /// in memory. This is the preferred way to process long logs on memory constrained devices.
+    ///
+    /// # Arguments
+    ///
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances. This parameter
+    ///   is used for decryption when working with encrypted logs (versions >= 13). If `None` is provided,
+    ///   the function will attempt to process the log without decryption.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result<FrameIter<RecordEnvelopeIter>>`. On success, it provides an iterator yielding
+    /// `Frame` instances in log order.
+    ///
+    pub fn frames_iter(
+        &self,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<FrameIter<RecordEnvelopeIter<'_>>> {
+        let envelopes = self.records_iter(keychains)?.envelopes();
+        Ok(FrameIter::new(envelopes, self.details.clone()))
+    }
+
+    /// Locks the underlying reader.
+    ///
+    /// Readers are only used for positioned reads, so a reader left by a panicking
+    /// thread is still usable.
+    ///
+    pub(crate) fn reader(&self) -> MutexGuard<'_, Box<dyn SeekRead + Send>> {
+        self.reader
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Collects the encoded keychains of the `KeyStorage` records yielded by `envelopes`, one
+/// keychain per `KeyStorageRecover` delimited section.
+///
+/// `KeyStorage` records decoded as `Record::Invalid`, e.g. cut off by a crash, are returned
+/// as failures.
+///
+fn key_storage_keychains(
+    envelopes: &mut RecordEnvelopeIter,
+) -> (Vec<Vec<EncodedKeychainFeaturePoint>>, Vec<ParseFailure>) {
+    let mut keychains = Vec::new();
+    let mut keychain: Vec<EncodedKeychainFeaturePoint> = Vec::new();
+    let mut cut_key_storage = Vec::new();
+
+    for envelope in envelopes {
+        match envelope.record {
+            Record::KeyStorage(data) => {
+                // add EncodedKeychainFeaturePoint to current keychain
+                keychain.push(EncodedKeychainFeaturePoint {
+                    feature_point: data.feature_point,
+                    aes_ciphertext: Base64Standard.encode(&data.data),
+                });
+            }
+            Record::KeyStorageRecover(_) => {
+                // start a new keychain
+                keychains.push(keychain);
+                keychain = Vec::new();
+            }
+            // A KeyStorage record without a valid framing, e.g. cut off by a crash
+            Record::Invalid(_)
+                if matches!(
+                    envelope.type_id,
+                    KEY_STORAGE_TYPE_ID | KEY_STORAGE_RECOVER_TYPE_ID
+                ) =>
+            {
+                cut_key_storage.push(ParseFailure {
+                    offset: envelope.offset,
+                    record_type: Some(envelope.type_id),
+                    error: Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "KeyStorage record is truncated or corrupted",
+                    )),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    keychains.push(keychain);
+
+    (keychains, cut_key_storage)
 }
@@ -0,0 +1,69 @@
+use serde::Serialize;
+
+use crate::record::waypoint::Waypoint;
+use crate::record::waypoint_mission::WaypointMission;
+use crate::record::Record;
+
+/// A waypoint mission planned within a DJI log, with its settings and waypoints.
+///
+/// Missions are built from the `WaypointMissionUpload` and `WaypointUpload` records written
+/// when the mission is sent to the aircraft, or from their `Download` counterparts written
+/// when it is read back. A mission record starts a new mission, and the waypoint records that
+/// follow fill its waypoints.
+///
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Mission {
+    /// Mission settings
+    pub settings: WaypointMission,
+    /// Mission waypoints, sorted by index
+    pub waypoints: Vec<Waypoint>,
+}
+
+impl Mission {
+    /// Collects the last mission planned in a sequence of records.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - The records of a log, in log order.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the last mission found, if any. Waypoints found before any mission
+    /// record are ignored.
+    ///
+    pub fn from_records(records: &[Record]) -> Option<Mission> {
+        let mut mission: Option<Mission> = None;
+
+        for record in records {
+            match record {
+                Record::WaypointMissionUpload(settings)
+                | Record::WaypointMissionDownload(settings) => {
+                    mission = Some(Mission {
+                        settings: settings.clone(),
+                        waypoints: Vec::with_capacity(settings.waypoint_count as usize),
+                    });
+                }
+                Record::WaypointUpload(waypoint) | Record::WaypointDownload(waypoint) => {
+                    if let Some(mission) = mission.as_mut() {
+                        match mission
+                            .waypoints
+                            .binary_search_by_key(&waypoint.index, |known| known.index)
+                        {
+                            Ok(position) => mission.waypoints[position] = waypoint.clone(),
+                            Err(position) => mission.waypoints.insert(position, waypoint.clone()),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        mission
+    }
+
+    /// Returns `true` if all the waypoints announced by the mission settings were found.
+    pub fn is_complete(&self) -> bool {
+        self.waypoints.len() == self.settings.waypoint_count as usize
+    }
+}
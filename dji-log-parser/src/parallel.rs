@@ -0,0 +1,318 @@
+use binrw::BinRead;
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Cursor, Seek, SeekFrom};
+
+use crate::decoder::{decode_payload, skip_record};
+use crate::iter::{has_end_byte, read_record_header};
+use crate::keychain::{FeaturePoint, Keychain};
+use crate::record::{Record, RecordEnvelope, JPEG_TYPE_ID, KEY_STORAGE_RECOVER_TYPE_ID};
+use crate::{DJILog, Error, ParseFailure, ParseReport, Result};
+
+/// A record found while scanning the records section.
+enum ScannedRecord {
+    /// Record to be decoded in parallel, along with the IV and key of its feature point
+    Pending {
+        offset: u64,
+        length: u64,
+        type_id: u8,
+        feature_point: FeaturePoint,
+        keychain_entry: Option<(Vec<u8>, Vec<u8>)>,
+    },
+    /// Record without a regular framing, already decoded during the scan
+    Decoded(RecordEnvelope),
+    /// Record that cannot be decoded, where the scan stopped
+    Failed { offset: u64, error: binrw::Error },
+}
+
+/// Outcome of the decoding of a scanned record.
+enum DecodedRecord {
+    /// Decoded record, along with the error of a failed decryption, if any
+    Decoded(RecordEnvelope, Option<Error>),
+    /// Record that cannot be decoded, at the given offset in the records section
+    Failed(u64, Error),
+}
+
+/// Decodes the records of `log` on all available cores.
+///
+/// The records section is loaded in memory and scanned sequentially for record boundaries.
+/// The scan also walks the AES IV chain of each feature point, so each record can then be
+/// decoded on its own with the IV it expects. Records without a regular framing (JPEG and
+/// invalid data) are decoded during the scan.
+///
+/// Decoding stops at the first record that cannot be decoded. The returned report is the
+/// one `RecordIter` produces for the same records.
+///
+pub(crate) fn decode_envelopes(
+    log: &DJILog,
+    keychains: Vec<Keychain>,
+) -> Result<(Vec<RecordEnvelope>, ParseReport)> {
+    let start_offset = log.prefix.records_offset();
+    let end_offset = log.prefix.records_end_offset(log.size);
+
+    // Bytes past the records section are kept, as invalid records may read into them
+    let mut buffer = Vec::new();
+    {
+        let mut reader = log.reader();
+        reader.seek(SeekFrom::Start(start_offset))?;
+        reader.read_to_end(&mut buffer)?;
+    }
+
+    let version = log.version;
+    let scanned = scan_records(
+        &buffer,
+        end_offset.saturating_sub(start_offset),
+        version,
+        keychains,
+    );
+
+    let decoded: Vec<DecodedRecord> = scanned
+        .into_par_iter()
+        .map(|scanned| match scanned {
+            ScannedRecord::Pending {
+                offset,
+                length,
+                type_id,
+                feature_point,
+                keychain_entry,
+            } => decode_record(
+                &buffer,
+                version,
+                offset,
+                length,
+                type_id,
+                feature_point,
+                keychain_entry,
+            ),
+            ScannedRecord::Decoded(envelope) => DecodedRecord::Decoded(envelope, None),
+            ScannedRecord::Failed { offset, error } => DecodedRecord::Failed(offset, error.into()),
+        })
+        .collect();
+
+    let mut report = ParseReport {
+        records_offset: start_offset,
+        records_end_offset: end_offset,
+        ..ParseReport::default()
+    };
+    let mut envelopes = Vec::with_capacity(decoded.len());
+
+    for decoded in decoded {
+        let (envelope, decryption_error) = match decoded {
+            DecodedRecord::Decoded(envelope, decryption_error) => (envelope, decryption_error),
+            DecodedRecord::Failed(offset, error) => {
+                // Stop at the first record that cannot be decoded, as `RecordIter` does
+                report.failures.push(ParseFailure {
+                    offset: start_offset + offset,
+                    record_type: buffer.get(offset as usize).copied(),
+                    error,
+                });
+                report.unparsed_bytes += end_offset.saturating_sub(start_offset + offset);
+                break;
+            }
+        };
+
+        match envelope.record {
+            Record::Unknown(..) => report.unknown_count += 1,
+            Record::Invalid(_) => report.invalid_count += 1,
+            _ => {}
+        }
+
+        if let Some(feature_point) = envelope.feature_point {
+            if let Some(error) = decryption_error {
+                let stats = report.decryption.entry(feature_point).or_default();
+                stats.records += 1;
+                match error {
+                    Error::InvalidPadding => stats.padding_errors += 1,
+                    _ => stats.other_errors += 1,
+                }
+                report.failures.push(ParseFailure {
+                    offset: start_offset + envelope.offset,
+                    record_type: Some(envelope.type_id),
+                    error,
+                });
+            } else if feature_point != FeaturePoint::PlaintextFeature
+                && !matches!(envelope.record, Record::JPEG(_) | Record::Invalid(_))
+            {
+                let stats = report.decryption.entry(feature_point).or_default();
+                stats.records += 1;
+                if envelope.missing_key {
+                    stats.missing_key += 1;
+                } else {
+                    stats.decrypted += 1;
+                }
+            }
+        }
+
+        envelopes.push(RecordEnvelope {
+            offset: start_offset + envelope.offset,
+            ..envelope
+        });
+    }
+
+    Ok((envelopes, report))
+}
+
+/// Decodes a framed record of the records section with the IV and key of its feature point.
+fn decode_record(
+    buffer: &[u8],
+    version: u8,
+    offset: u64,
+    length: u64,
+    type_id: u8,
+    feature_point: FeaturePoint,
+    keychain_entry: Option<(Vec<u8>, Vec<u8>)>,
+) -> DecodedRecord {
+    let missing_key = version >= 13
+        && feature_point != FeaturePoint::PlaintextFeature
+        && keychain_entry.is_none();
+
+    let mut keychain = Keychain::empty();
+    if let Some(value) = keychain_entry.clone() {
+        keychain.insert(feature_point, value);
+    }
+
+    let keychain = RefCell::new(keychain);
+
+    let mut cursor = Cursor::new(buffer);
+    cursor.set_position(offset);
+
+    let record = match Record::read_args(
+        &mut cursor,
+        binrw::args! {
+            version,
+            keychain: &keychain
+        },
+    ) {
+        Ok(record) => record,
+        Err(error) => return DecodedRecord::Failed(offset, error.into()),
+    };
+
+    // An encrypted record which cannot be decrypted falls back to `Record::Invalid`.
+    // Decode it again to report the typed decoding error.
+    let mut decryption_error = None;
+    if let (Record::Invalid(_), Some(value)) = (&record, keychain_entry) {
+        let header_size = if version <= 12 { 2 } else { 3 };
+        keychain.borrow_mut().insert(feature_point, value);
+        cursor.set_position(offset + header_size);
+        decryption_error = decode_payload(
+            &mut cursor,
+            type_id,
+            version,
+            &keychain,
+            (length - header_size - 1) as u16,
+        )
+        .err()
+        .map(Error::from);
+    }
+
+    DecodedRecord::Decoded(
+        RecordEnvelope {
+            offset,
+            length,
+            type_id,
+            feature_point: (version >= 13).then_some(feature_point),
+            missing_key,
+            record,
+            payload: None,
+        },
+        decryption_error,
+    )
+}
+
+/// Scans the records section for record boundaries, in log order.
+fn scan_records(
+    buffer: &[u8],
+    end_offset: u64,
+    version: u8,
+    keychains: Vec<Keychain>,
+) -> Vec<ScannedRecord> {
+    let mut keychains = VecDeque::from(keychains);
+    let keychain = RefCell::new(keychains.pop_front().unwrap_or(Keychain::empty()));
+
+    let mut cursor = Cursor::new(buffer);
+    let mut scanned = Vec::new();
+    let mut position = 0;
+
+    while position < end_offset {
+        // Records of at most 2 bytes cannot be decoded as `Record::Unknown`, so they are
+        // decoded as `Record::Invalid`, which does not stop at the framed end
+        let header = read_record_header(&mut cursor, position, version).filter(
+            |(type_id, header_size, length)| {
+                *type_id != JPEG_TYPE_ID
+                    && *length > 2
+                    && has_end_byte(&mut cursor, position, *header_size, *length, end_offset)
+            },
+        );
+
+        if let Some((type_id, header_size, length)) = header {
+            let feature_point = FeaturePoint::from_record_type(type_id, version);
+            let keychain_entry = keychain.borrow().get(&feature_point).cloned();
+
+            // Move the IV chain past the record, as decoding it would
+            let skipped = cursor
+                .seek(SeekFrom::Start(position + header_size))
+                .and_then(|_| skip_record(&mut cursor, type_id, version, &keychain, length as u16));
+
+            if skipped.is_ok() {
+                scanned.push(ScannedRecord::Pending {
+                    offset: position,
+                    length: header_size + length + 1,
+                    type_id,
+                    feature_point,
+                    keychain_entry: keychain_entry.filter(|_| version >= 13),
+                });
+                position += header_size + length + 1;
+
+                if type_id == KEY_STORAGE_RECOVER_TYPE_ID {
+                    *keychain.borrow_mut() = keychains.pop_front().unwrap_or(Keychain::empty());
+                }
+                continue;
+            }
+        }
+
+        // Irregular record, decode it right away
+        cursor.set_position(position);
+        let record = Record::read_args(
+            &mut cursor,
+            binrw::args! {
+                version,
+                keychain: &keychain
+            },
+        );
+
+        match record {
+            Ok(record) => {
+                let type_id = buffer.get(position as usize).copied().unwrap_or_default();
+                let feature_point = FeaturePoint::from_record_type(type_id, version);
+                let missing_key = version >= 13
+                    && feature_point != FeaturePoint::PlaintextFeature
+                    && !matches!(record, Record::JPEG(_) | Record::Invalid(_))
+                    && keychain.borrow().get(&feature_point).is_none();
+
+                if let Record::KeyStorageRecover(_) = record {
+                    *keychain.borrow_mut() = keychains.pop_front().unwrap_or(Keychain::empty());
+                }
+                scanned.push(ScannedRecord::Decoded(RecordEnvelope {
+                    offset: position,
+                    length: cursor.position() - position,
+                    type_id,
+                    feature_point: (version >= 13).then_some(feature_point),
+                    missing_key,
+                    record,
+                    payload: None,
+                }));
+                position = cursor.position();
+            }
+            Err(error) => {
+                scanned.push(ScannedRecord::Failed {
+                    offset: position,
+                    error,
+                });
+                break;
+            }
+        }
+    }
+
+    scanned
+}
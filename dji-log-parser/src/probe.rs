@@ -0,0 +1,147 @@
+use binrw::io::Cursor;
+use binrw::BinRead;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::iter::{has_end_byte, read_record_header};
+use crate::layout::auxiliary::{read_auxiliary_blocks, Auxiliary};
+use crate::layout::details::{Details, ProductType};
+use crate::layout::prefix::{Prefix, INFO_SIZE, PREFIX_SIZE};
+use crate::utils::pad_with_zeros;
+use crate::{Error, Result};
+
+/// General information about a DJI log, as returned by `probe`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeResult {
+    /// Log version
+    pub version: u8,
+    /// Whether records are encrypted, requiring keychains to be decoded (versions >= 13)
+    pub needs_keychain: bool,
+    /// Product type, if the details are within the probed bytes
+    pub product_type: Option<ProductType>,
+    /// Flight start time, if the details are within the probed bytes
+    pub start_time: Option<DateTime<Utc>>,
+    /// Aircraft name, if the details are within the probed bytes
+    pub aircraft_name: Option<String>,
+}
+
+/// Checks whether `bytes` hold a DJI log and reads its version and general information.
+///
+/// Only the `Prefix`, the `Details` or `Auxiliary` blocks and the first record are read, so
+/// this is much cheaper than constructing a `DJILog`. For logs versions >= 12, the first few
+/// hundred bytes of the file are enough. Before version 12, details are written at the end of
+/// the file and are only returned when `bytes` hold the whole file.
+///
+/// # Arguments
+///
+/// * `bytes` - The start of the file, or the whole file.
+///
+/// # Returns
+///
+/// This function returns `Result<ProbeResult>`. It returns `Error::NotDJILog` when `bytes` do
+/// not start with a DJI log.
+///
+/// # Examples
+///
+/// ```ignore
+/// use dji_log_parser::probe;
+///
+/// let result = probe(&bytes)?;
+/// if result.needs_keychain {
+///     // fetch keychains
+/// }
+/// ```
+///
+pub fn probe(bytes: &[u8]) -> Result<ProbeResult> {
+    let prefix = Prefix::read(&mut Cursor::new(bytes))
+        .map_err(|_| Error::NotDJILog("file shorter than the prefix".into()))?;
+
+    let version = prefix.version;
+    if version == 0 {
+        return Err(Error::NotDJILog("invalid version 0".into()));
+    }
+
+    let details = if version < 13 {
+        check_first_record(bytes, &prefix)?;
+
+        // The details offset is only set once the flight is over before version 12
+        let detail_offset = prefix.detail_offset();
+        let has_details = version == 12 || detail_offset >= prefix.records_offset();
+
+        bytes
+            .get(detail_offset as usize..)
+            // A shorter block is most likely cut by the end of the probed bytes
+            .filter(|block| has_details && (block.len() >= 400 || version < 6))
+            .map(|block| {
+                let block = &block[..block.len().min(INFO_SIZE as usize)];
+                Details::read_args(&mut Cursor::new(pad_with_zeros(block, 400)), (version,))
+            })
+            .transpose()
+            .map_err(|_| Error::NotDJILog("invalid details block".into()))?
+    } else {
+        let records_offset = Some(prefix.records_offset()).filter(|offset| *offset != 0);
+        let blocks = read_auxiliary_blocks(&mut Cursor::new(bytes), PREFIX_SIZE, records_offset)
+            .map(|(blocks, _)| blocks)
+            .map_err(|_| Error::NotDJILog("invalid auxiliary blocks".into()))?;
+
+        match blocks
+            .iter()
+            .find(|block| matches!(block, Auxiliary::Info(_)))
+        {
+            Some(Auxiliary::Info(data)) => Some(
+                Details::read_args(&mut Cursor::new(&data.info_data), (version,))
+                    .map_err(|_| Error::NotDJILog("invalid details block".into()))?,
+            ),
+            _ => return Err(Error::NotDJILog("missing auxiliary info block".into())),
+        }
+    };
+
+    Ok(ProbeResult {
+        version,
+        needs_keychain: version >= 13,
+        product_type: details.as_ref().map(|details| details.product_type),
+        start_time: details.as_ref().map(|details| details.start_time),
+        aircraft_name: details.map(|details| details.aircraft_name),
+    })
+}
+
+/// Checks that the records section starts with a framed record, unless it is empty.
+///
+/// Only the framing is checked, i.e. the length prefix fits in the records section and the end
+/// byte follows the record content, so logs starting with a record type unknown to the parser
+/// are accepted. A first record cut by the end of the probed bytes is inconclusive, so it is
+/// accepted.
+///
+fn check_first_record(bytes: &[u8], prefix: &Prefix) -> Result<()> {
+    let records_offset = prefix.records_offset();
+    let mut end_offset = prefix.records_end_offset(bytes.len() as u64);
+
+    // The details offset is unknown while a log is being written
+    if end_offset <= records_offset {
+        end_offset = bytes.len() as u64;
+    }
+
+    if records_offset >= end_offset {
+        return Ok(());
+    }
+
+    let mut cursor = Cursor::new(bytes);
+    let Some((_, header_size, length)) =
+        read_record_header(&mut cursor, records_offset, prefix.version)
+    else {
+        // The header itself is cut
+        return Ok(());
+    };
+
+    let end_byte_offset = records_offset + header_size + length;
+    if end_byte_offset >= bytes.len() as u64 {
+        return Ok(());
+    }
+
+    if !has_end_byte(&mut cursor, records_offset, header_size, length, end_offset) {
+        return Err(Error::NotDJILog("invalid first record".into()));
+    }
+
+    Ok(())
+}
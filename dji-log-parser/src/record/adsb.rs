@@ -0,0 +1,98 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+/// Manned aircraft traffic detected by AirSense, after processing by the flight controller.
+///
+/// Experimental: the layout was not checked against captured logs.
+///
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct ADSBFlightData {
+    #[br(temp)]
+    count: u8,
+    #[br(count = count as usize)]
+    pub aircraft: Vec<ADSBAircraft>,
+}
+
+/// Manned aircraft traffic as received by the AirSense ADS-B receiver.
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct ADSBFlightOriginal {
+    #[br(temp)]
+    count: u8,
+    #[br(count = count as usize)]
+    pub aircraft: Vec<ADSBAircraft>,
+}
+
+#[binread]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct ADSBAircraft {
+    /// 24-bit ICAO address of the aircraft
+    pub icao_address: u32,
+    #[br(count = 8, map = |s: Vec<u8>| String::from_utf8_lossy(&s).trim_end_matches('\0').trim_end().to_string())]
+    pub call_sign: String,
+    /// degrees
+    #[br(map = |x: i32| x as f64 / 1e7)]
+    pub latitude: f64,
+    /// degrees
+    #[br(map = |x: i32| x as f64 / 1e7)]
+    pub longitude: f64,
+    /// meters
+    #[br(map = |x: i32| x as f32 / 1000.0)]
+    pub altitude: f32,
+    /// degrees, clockwise from true north
+    #[br(map = |x: u16| x as f32 / 100.0)]
+    pub heading: f32,
+    /// meters per second
+    #[br(map = |x: u16| x as f32 / 100.0)]
+    pub horizontal_speed: f32,
+    /// meters per second, positive when climbing
+    #[br(map = |x: i16| x as f32 / 100.0)]
+    pub vertical_speed: f32,
+    #[br(map = |x: u8| ADSBWarningLevel::from(x))]
+    pub warning_level: ADSBWarningLevel,
+}
+
+impl ADSBAircraft {
+    /// Returns the ICAO address as 6 hexadecimal digits, as displayed by flight trackers.
+    pub fn icao_hex(&self) -> String {
+        format!("{:06X}", self.icao_address & 0xFF_FFFF)
+    }
+}
+
+/// AirSense threat level of an aircraft, from `None` to `Critical`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum ADSBWarningLevel {
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for ADSBWarningLevel {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => ADSBWarningLevel::None,
+            1 => ADSBWarningLevel::Low,
+            2 => ADSBWarningLevel::Medium,
+            3 => ADSBWarningLevel::High,
+            4 => ADSBWarningLevel::Critical,
+            _ => ADSBWarningLevel::Unknown(value),
+        }
+    }
+}
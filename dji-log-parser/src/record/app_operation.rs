@@ -0,0 +1,20 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+/// Button pressed by the pilot in the app.
+///
+/// Operation codes are not documented by DJI, so they are kept raw.
+///
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct AppOperation {
+    /// Operation code
+    pub operation: u8,
+    /// Operation specific value
+    pub value: u8,
+}
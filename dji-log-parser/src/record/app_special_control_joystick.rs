@@ -0,0 +1,32 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::utils::sub_byte_field;
+
+/// On-screen joysticks of the app, used to fly without remote controller.
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct AppSpecialControlJoyStick {
+    #[br(temp)]
+    _bitpack1: u8,
+    #[br(calc(sub_byte_field(_bitpack1, 0x01) == 1))]
+    pub is_enabled: bool,
+
+    /// Right joystick horizontal position, [-1, 1] from left to right
+    #[br(map = |x: i16| x as f32 / 1000.0)]
+    pub roll: f32,
+    /// Right joystick vertical position, [-1, 1] from back to front
+    #[br(map = |x: i16| x as f32 / 1000.0)]
+    pub pitch: f32,
+    /// Left joystick horizontal position, [-1, 1] from left to right
+    #[br(map = |x: i16| x as f32 / 1000.0)]
+    pub yaw: f32,
+    /// Left joystick vertical position, [-1, 1] from down to up
+    #[br(map = |x: i16| x as f32 / 1000.0)]
+    pub throttle: f32,
+}
@@ -0,0 +1,60 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::utils::sub_byte_field;
+
+/// Progress of the waypoint mission being flown.
+///
+/// Experimental: the layout was not checked against captured logs.
+///
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct GSMissionStatus {
+    #[br(map = |x: u8| WaypointMissionState::from(x))]
+    pub state: WaypointMissionState,
+    /// Index of the waypoint the aircraft is flying to
+    pub target_waypoint_index: u8,
+
+    #[br(temp)]
+    _bitpack1: u8,
+    #[br(calc(sub_byte_field(_bitpack1, 0x01) == 1))]
+    pub is_waypoint_reached: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointMissionState {
+    Initializing,
+    Moving,
+    CurveModeMoving,
+    CurveModeTurning,
+    BeginAction,
+    DoingAction,
+    FinishedAction,
+    ReturnToFirstWaypoint,
+    Paused,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointMissionState {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointMissionState::Initializing,
+            1 => WaypointMissionState::Moving,
+            2 => WaypointMissionState::CurveModeMoving,
+            3 => WaypointMissionState::CurveModeTurning,
+            4 => WaypointMissionState::BeginAction,
+            5 => WaypointMissionState::DoingAction,
+            6 => WaypointMissionState::FinishedAction,
+            7 => WaypointMissionState::ReturnToFirstWaypoint,
+            8 => WaypointMissionState::Paused,
+            _ => WaypointMissionState::Unknown(value),
+        }
+    }
+}
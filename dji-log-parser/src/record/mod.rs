@@ -5,12 +5,16 @@ use std::cell::RefCell;
 use tsify_next::Tsify;
 
 use crate::decoder::record_decoder;
+use crate::keychain::FeaturePoint;
 use crate::layout::details::ProductType;
 use crate::utils;
 use crate::Keychain;
 
+pub mod adsb;
 pub mod app_gps;
+pub mod app_operation;
 pub mod app_serious_warn;
+pub mod app_special_control_joystick;
 pub mod app_tip;
 pub mod app_warn;
 pub mod camera;
@@ -20,6 +24,7 @@ pub mod custom;
 pub mod deform;
 pub mod firmware;
 pub mod gimbal;
+pub mod gs_mission_status;
 pub mod home;
 pub mod key_storage;
 pub mod mc_param;
@@ -32,9 +37,16 @@ pub mod recover;
 pub mod smart_battery;
 pub mod smart_battery_group;
 pub mod virtual_stick;
+pub mod vision_group;
+pub mod vision_warning;
+pub mod waypoint;
+pub mod waypoint_mission;
 
+use adsb::{ADSBFlightData, ADSBFlightOriginal};
 use app_gps::AppGPS;
+use app_operation::AppOperation;
 use app_serious_warn::AppSeriousWarn;
+use app_special_control_joystick::AppSpecialControlJoyStick;
 use app_tip::AppTip;
 use app_warn::AppWarn;
 use camera::Camera;
@@ -44,6 +56,7 @@ use custom::Custom;
 use deform::Deform;
 use firmware::Firmware;
 use gimbal::Gimbal;
+use gs_mission_status::GSMissionStatus;
 use home::Home;
 use key_storage::KeyStorage;
 use mc_param::MCParams;
@@ -56,8 +69,66 @@ use recover::Recover;
 use smart_battery::SmartBattery;
 use smart_battery_group::*;
 use virtual_stick::VirtualStick;
+use vision_group::VisionGroup;
+use vision_warning::VisionWarning;
+use waypoint::Waypoint;
+use waypoint_mission::WaypointMission;
 
-const END_BYTE: u8 = 0xFF;
+pub(crate) const END_BYTE: u8 = 0xFF;
+
+/// Record type ids decoded into a dedicated `Record` variant, along with the variant name.
+///
+/// Experimental records, whose layout was not checked against captured logs, are decoded into
+/// `Record::Unknown` unless the `experimental-records` feature is enabled.
+pub(crate) const KNOWN_RECORD_TYPES: &[(u8, &str)] = &[
+    (1, "OSD"),
+    (2, "Home"),
+    (3, "Gimbal"),
+    (4, "RC"),
+    (5, "Custom"),
+    (6, "Deform"),
+    (7, "CenterBattery"),
+    (8, "SmartBattery"),
+    (9, "AppTip"),
+    (10, "AppWarn"),
+    (11, "RCGPS"),
+    (13, "Recover"),
+    (14, "AppGPS"),
+    (15, "Firmware"),
+    (17, "VisionGroup"),
+    (18, "VisionWarning"),
+    (19, "MCParams"),
+    (20, "AppOperation"),
+    (22, "SmartBatteryGroup"),
+    (24, "AppSeriousWarn"),
+    (25, "Camera"),
+    (26, "ADSBFlightData"),
+    (27, "ADSBFlightOriginal"),
+    (29, "AppSpecialControlJoyStick"),
+    (32, "GSMissionStatus"),
+    (33, "VirtualStick"),
+    (35, "WaypointMissionUpload"),
+    (36, "WaypointUpload"),
+    (38, "WaypointMissionDownload"),
+    (39, "WaypointDownload"),
+    (40, "ComponentSerial"),
+    (49, "OFDM"),
+    (50, "KeyStorageRecover"),
+    (56, "KeyStorage"),
+    (62, "RCDisplayField"),
+];
+
+/// Whether experimental records are decoded into their dedicated `Record` variant.
+const EXPERIMENTAL_RECORDS: bool = cfg!(feature = "experimental-records");
+
+/// Type id of `Record::JPEG` records, i.e. the first byte of the JPEG start marker.
+pub(crate) const JPEG_TYPE_ID: u8 = 0xFF;
+
+/// Type id of `Record::KeyStorage` records, which hold the encrypted keychain entries.
+pub(crate) const KEY_STORAGE_TYPE_ID: u8 = 56;
+
+/// Type id of `Record::KeyStorageRecover` records, which switch to the next keychain.
+pub(crate) const KEY_STORAGE_RECOVER_TYPE_ID: u8 = 50;
 
 /// Represents the different types of records.
 ///
@@ -219,6 +290,28 @@ pub enum Record {
         Firmware,
         #[br(temp, assert(self_2 == END_BYTE))] u8,
     ),
+    #[br(magic = 17u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    VisionGroup(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 17, version, keychain, self_0),
+            args { version }
+        )]
+        VisionGroup,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 18u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    VisionWarning(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 18, version, keychain, self_0),
+            args { length: if version <= 6 { self_0 } else { self_0.saturating_sub(2) } }
+        )]
+        VisionWarning,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
     #[br(magic = 19u8)]
     MCParams(
         #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
@@ -229,6 +322,16 @@ pub enum Record {
         MCParams,
         #[br(temp, assert(self_2 == END_BYTE))] u8,
     ),
+    #[br(magic = 20u8)]
+    AppOperation(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 20, version, keychain, self_0),
+        )]
+        AppOperation,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
     #[br(magic = 22u8)]
     SmartBatteryGroup(
         #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
@@ -260,6 +363,46 @@ pub enum Record {
         Camera,
         #[br(temp, assert(self_2 == END_BYTE))] u8,
     ),
+    #[br(magic = 26u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    ADSBFlightData(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 26, version, keychain, self_0),
+        )]
+        ADSBFlightData,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 27u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    ADSBFlightOriginal(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 27, version, keychain, self_0),
+        )]
+        ADSBFlightOriginal,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 29u8)]
+    AppSpecialControlJoyStick(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 29, version, keychain, self_0),
+        )]
+        AppSpecialControlJoyStick,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 32u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    GSMissionStatus(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 32, version, keychain, self_0),
+        )]
+        GSMissionStatus,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
     #[br(magic = 33u8)]
     VirtualStick(
         #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
@@ -270,6 +413,46 @@ pub enum Record {
         VirtualStick,
         #[br(temp, assert(self_2 == END_BYTE))] u8,
     ),
+    #[br(magic = 35u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    WaypointMissionUpload(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 35, version, keychain, self_0),
+        )]
+        WaypointMission,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 36u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    WaypointUpload(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 36, version, keychain, self_0),
+        )]
+        Waypoint,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 38u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    WaypointMissionDownload(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 38, version, keychain, self_0),
+        )]
+        WaypointMission,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
+    #[br(magic = 39u8, pre_assert(EXPERIMENTAL_RECORDS))]
+    WaypointDownload(
+        #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
+        #[br(
+            pad_size_to = self_0,
+            map_stream = |reader| record_decoder(reader, 39, version, keychain, self_0),
+        )]
+        Waypoint,
+        #[br(temp, assert(self_2 == END_BYTE))] u8,
+    ),
     #[br(magic = 40u8)]
     ComponentSerial(
         #[br(temp, args(version <= 12), parse_with = utils::read_u16)] u16,
@@ -336,3 +519,34 @@ pub enum Record {
     // Invalid data, try to seek to next record
     Invalid(#[br(parse_with = utils::seek_to_next_record, assert(!self_0.is_empty()))] Vec<u8>),
 }
+
+/// A `Record` along with its framing in the log file.
+///
+/// Envelopes keep the information dropped once a record is parsed, which is useful
+/// to cross-reference records with an hex dump of the log or to reverse-engineer
+/// unknown record types.
+///
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct RecordEnvelope {
+    /// Byte offset of the record in the log file
+    pub offset: u64,
+    /// Length of the record in bytes, including type id, length prefix and end byte
+    pub length: u64,
+    /// Raw record type id, i.e. the first byte of the record
+    pub type_id: u8,
+    /// Feature point used to decrypt the record (versions >= 13)
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub feature_point: Option<FeaturePoint>,
+    /// `true` if the keychain has no key for the feature point of the record, which was
+    /// then only XOR decoded: its content is unreliable
+    pub missing_key: bool,
+    /// Parsed record
+    pub record: Record,
+    /// Plaintext record content, as given to `DJILogWriter::write_record`. Only set when
+    /// iterating with `RecordIter::with_payloads`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(target_arch = "wasm32", tsify(optional))]
+    pub payload: Option<Vec<u8>>,
+}
@@ -0,0 +1,180 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::utils::sub_byte_field;
+
+/// Vision system state, tagged by its first byte.
+///
+/// Experimental: the layout, including the thresholds read from version 10, was not checked
+/// against captured logs.
+///
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(tag = "type")]
+#[br(little, import { version: u8 })]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum VisionGroup {
+    #[br(magic = 1u8)]
+    VisionAvoidanceState(#[br(args { version })] VisionAvoidanceState),
+    #[br(magic = 2u8)]
+    VisionObstacleDistances(#[br(args { version })] VisionObstacleDistances),
+    #[br(magic = 3u8)]
+    VisionBraking(VisionBraking),
+}
+
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little, import { version: u8 })]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VisionAvoidanceState {
+    #[br(temp)]
+    _bitpack1: u8,
+    #[br(calc(sub_byte_field(_bitpack1, 0x01) == 1))]
+    pub is_avoidance_enabled: bool,
+    #[br(calc(sub_byte_field(_bitpack1, 0x02) == 1))]
+    pub is_braking: bool,
+    #[br(calc(VisionAvoidanceMode::from(sub_byte_field(_bitpack1, 0x0C))))]
+    pub avoidance_mode: VisionAvoidanceMode,
+    #[br(calc(sub_byte_field(_bitpack1, 0x10) == 1))]
+    pub is_bypassing: bool,
+
+    #[br(temp)]
+    _bitpack2: u8,
+    #[br(calc(sub_byte_field(_bitpack2, 0x01) == 1))]
+    pub is_front_working: bool,
+    #[br(calc(sub_byte_field(_bitpack2, 0x02) == 1))]
+    pub is_back_working: bool,
+    #[br(calc(sub_byte_field(_bitpack2, 0x04) == 1))]
+    pub is_left_working: bool,
+    #[br(calc(sub_byte_field(_bitpack2, 0x08) == 1))]
+    pub is_right_working: bool,
+    #[br(calc(sub_byte_field(_bitpack2, 0x10) == 1))]
+    pub is_up_working: bool,
+    #[br(calc(sub_byte_field(_bitpack2, 0x20) == 1))]
+    pub is_down_working: bool,
+
+    /// meters, distance at which the aircraft brakes in front of an obstacle
+    #[br(if(version >= 10), map = |x: u16| x as f32 / 100.0)]
+    pub braking_distance: f32,
+    /// meters, distance at which obstacles are reported
+    #[br(if(version >= 10), map = |x: u16| x as f32 / 100.0)]
+    pub warning_distance: f32,
+}
+
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little, import { version: u8 })]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VisionObstacleDistances {
+    #[br(map = |x: u8| VisionDirection::from(x))]
+    pub direction: VisionDirection,
+    pub sector_count: u8,
+    /// meters, from left to right as seen from the sensor, 0 when no obstacle is detected
+    #[br(count = sector_count as usize, map = |xs: Vec<u16>| xs.into_iter().map(|x| x as f32 / 100.0).collect())]
+    pub distances: Vec<f32>,
+    #[br(if(version >= 10), map = |x: u8| VisionWarningLevel::from(x))]
+    pub warning_level: VisionWarningLevel,
+}
+
+impl VisionObstacleDistances {
+    /// Returns the distance of the closest obstacle in meters, if any.
+    pub fn closest_distance(&self) -> Option<f32> {
+        self.distances
+            .iter()
+            .copied()
+            .filter(|distance| *distance > 0.0)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VisionBraking {
+    #[br(map = |x: u8| VisionDirection::from(x))]
+    pub direction: VisionDirection,
+    /// meters, distance of the obstacle when braking started
+    #[br(map = |x: u16| x as f32 / 100.0)]
+    pub distance: f32,
+    /// meters / sec, speed when braking started
+    #[br(map = |x: u16| x as f32 / 100.0)]
+    pub speed: f32,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum VisionDirection {
+    Front,
+    Back,
+    Left,
+    Right,
+    Up,
+    Down,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for VisionDirection {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => VisionDirection::Front,
+            1 => VisionDirection::Back,
+            2 => VisionDirection::Left,
+            3 => VisionDirection::Right,
+            4 => VisionDirection::Up,
+            5 => VisionDirection::Down,
+            _ => VisionDirection::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum VisionAvoidanceMode {
+    Off,
+    Brake,
+    Bypass,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for VisionAvoidanceMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => VisionAvoidanceMode::Off,
+            1 => VisionAvoidanceMode::Brake,
+            2 => VisionAvoidanceMode::Bypass,
+            _ => VisionAvoidanceMode::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum VisionWarningLevel {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for VisionWarningLevel {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => VisionWarningLevel::None,
+            1 => VisionWarningLevel::Low,
+            2 => VisionWarningLevel::Medium,
+            3 => VisionWarningLevel::High,
+            _ => VisionWarningLevel::Unknown(value),
+        }
+    }
+}
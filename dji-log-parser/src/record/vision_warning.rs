@@ -0,0 +1,14 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+#[binread]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[br(little, import { length: u16 })]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct VisionWarning {
+    #[br(count = length as usize, map = |s: Vec<u8>| String::from_utf8_lossy(&s).trim_end_matches('\0').to_string())]
+    pub message: String,
+}
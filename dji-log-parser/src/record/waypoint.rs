@@ -0,0 +1,104 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+/// A waypoint of a waypoint mission, as uploaded to or downloaded from the aircraft.
+///
+/// Experimental: the layout was not checked against captured logs.
+///
+#[binread]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct Waypoint {
+    /// Index of the waypoint in the mission, starting at 0
+    pub index: u8,
+    /// degrees
+    pub latitude: f64,
+    /// degrees
+    pub longitude: f64,
+    /// meters, relative to the takeoff point
+    pub altitude: f32,
+    /// degrees, clockwise from north, used with `UsingWaypointHeading` heading mode
+    pub heading: i16,
+    /// meters, used with `Curved` flight path mode
+    pub corner_radius: f32,
+    #[br(map = |x: u8| WaypointTurnMode::from(x))]
+    pub turn_mode: WaypointTurnMode,
+    /// degrees
+    pub gimbal_pitch: i16,
+    /// meters / sec, 0 to use the mission speed
+    pub speed: f32,
+    /// Number of times the waypoint actions are performed
+    pub action_repeat_times: u8,
+    /// seconds, maximum time spent performing the waypoint actions
+    pub action_timeout: u16,
+    #[br(temp)]
+    action_count: u8,
+    #[br(count = action_count as usize)]
+    pub actions: Vec<WaypointAction>,
+}
+
+#[binread]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct WaypointAction {
+    #[br(map = |x: u8| WaypointActionType::from(x))]
+    pub action_type: WaypointActionType,
+    /// Action parameter, e.g. milliseconds for `Stay` or degrees for `RotateAircraft`
+    pub param: i16,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointTurnMode {
+    Clockwise,
+    CounterClockwise,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointTurnMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointTurnMode::Clockwise,
+            1 => WaypointTurnMode::CounterClockwise,
+            _ => WaypointTurnMode::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointActionType {
+    Stay,
+    StartTakePhoto,
+    StartRecord,
+    StopRecord,
+    RotateAircraft,
+    GimbalPitch,
+    CameraZoom,
+    CameraFocus,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointActionType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointActionType::Stay,
+            1 => WaypointActionType::StartTakePhoto,
+            2 => WaypointActionType::StartRecord,
+            3 => WaypointActionType::StopRecord,
+            4 => WaypointActionType::RotateAircraft,
+            5 => WaypointActionType::GimbalPitch,
+            7 => WaypointActionType::CameraZoom,
+            8 => WaypointActionType::CameraFocus,
+            _ => WaypointActionType::Unknown(value),
+        }
+    }
+}
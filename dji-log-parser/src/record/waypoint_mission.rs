@@ -0,0 +1,140 @@
+use binrw::binread;
+use serde::Serialize;
+#[cfg(target_arch = "wasm32")]
+use tsify_next::Tsify;
+
+use crate::utils::sub_byte_field;
+
+/// Settings of a waypoint mission, as uploaded to or downloaded from the aircraft.
+///
+/// The mission waypoints follow in `Waypoint` records.
+///
+/// Experimental: the layout was not checked against captured logs.
+///
+#[binread]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[br(little)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub struct WaypointMission {
+    pub waypoint_count: u8,
+    /// meters / sec, speed the pilot can reach with the remote controller during the mission
+    pub max_flight_speed: f32,
+    /// meters / sec, default speed between waypoints
+    pub auto_flight_speed: f32,
+    #[br(map = |x: u8| WaypointMissionFinishedAction::from(x))]
+    pub finished_action: WaypointMissionFinishedAction,
+    #[br(map = |x: u8| WaypointMissionHeadingMode::from(x))]
+    pub heading_mode: WaypointMissionHeadingMode,
+    #[br(map = |x: u8| WaypointMissionFlightPathMode::from(x))]
+    pub flight_path_mode: WaypointMissionFlightPathMode,
+    #[br(map = |x: u8| WaypointMissionGotoFirstWaypointMode::from(x))]
+    pub goto_first_waypoint_mode: WaypointMissionGotoFirstWaypointMode,
+
+    #[br(temp)]
+    _bitpack1: u8,
+    #[br(calc(sub_byte_field(_bitpack1, 0x01) == 1))]
+    pub exit_mission_on_rc_signal_lost: bool,
+    #[br(calc(sub_byte_field(_bitpack1, 0x02) == 1))]
+    pub is_gimbal_pitch_rotation_enabled: bool,
+
+    /// Number of times the mission is flown
+    pub repeat_times: u8,
+    /// degrees, point of interest the aircraft faces with `TowardPointOfInterest` heading mode
+    pub poi_latitude: f64,
+    /// degrees
+    pub poi_longitude: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointMissionFinishedAction {
+    NoAction,
+    GoHome,
+    AutoLand,
+    GoFirstWaypoint,
+    ContinueUntilStop,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointMissionFinishedAction {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointMissionFinishedAction::NoAction,
+            1 => WaypointMissionFinishedAction::GoHome,
+            2 => WaypointMissionFinishedAction::AutoLand,
+            3 => WaypointMissionFinishedAction::GoFirstWaypoint,
+            4 => WaypointMissionFinishedAction::ContinueUntilStop,
+            _ => WaypointMissionFinishedAction::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointMissionHeadingMode {
+    /// Aircraft faces the direction of flight
+    Auto,
+    UsingInitialDirection,
+    ControlledByRC,
+    UsingWaypointHeading,
+    TowardPointOfInterest,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointMissionHeadingMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointMissionHeadingMode::Auto,
+            1 => WaypointMissionHeadingMode::UsingInitialDirection,
+            2 => WaypointMissionHeadingMode::ControlledByRC,
+            3 => WaypointMissionHeadingMode::UsingWaypointHeading,
+            4 => WaypointMissionHeadingMode::TowardPointOfInterest,
+            _ => WaypointMissionHeadingMode::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointMissionFlightPathMode {
+    /// Straight lines between waypoints
+    Normal,
+    /// Curves around waypoints, following their corner radius
+    Curved,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointMissionFlightPathMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointMissionFlightPathMode::Normal,
+            1 => WaypointMissionFlightPathMode::Curved,
+            _ => WaypointMissionFlightPathMode::Unknown(value),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[cfg_attr(target_arch = "wasm32", derive(Tsify))]
+pub enum WaypointMissionGotoFirstWaypointMode {
+    /// Climbs to the first waypoint altitude before flying to it
+    Safely,
+    /// Flies straight to the first waypoint
+    PointToPoint,
+    #[serde(untagged)]
+    Unknown(u8),
+}
+
+impl From<u8> for WaypointMissionGotoFirstWaypointMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WaypointMissionGotoFirstWaypointMode::Safely,
+            1 => WaypointMissionGotoFirstWaypointMode::PointToPoint,
+            _ => WaypointMissionGotoFirstWaypointMode::Unknown(value),
+        }
+    }
+}
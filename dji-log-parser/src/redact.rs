@@ -0,0 +1,410 @@
+use crc64::crc64;
+use std::io::{Seek, Write};
+
+use crate::frame::Frame;
+use crate::iter::RecordCursor;
+use crate::keychain::KeychainFeaturePoint;
+use crate::layout::details::{parse_battery_sn, Details, ProductType};
+use crate::record::adsb::{ADSBFlightData, ADSBFlightOriginal};
+use crate::record::{Record, KEY_STORAGE_RECOVER_TYPE_ID};
+use crate::writer::encode_battery_sn;
+use crate::{DJILog, DJILogWriter, Error, Result};
+
+/// How serial numbers are redacted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerialRedaction {
+    /// Serial numbers are replaced by an empty string
+    #[default]
+    Blank,
+    /// Serial numbers are replaced by a keyed hash of 16 digits, or of the size of the serial
+    /// number field when shorter, so the same serial number is redacted to the same value
+    /// across logs redacted with the same secret
+    Hash,
+}
+
+/// Anonymization settings used to share a DJI log.
+///
+/// A redaction blanks or hashes the aircraft, camera, remote control and battery serial
+/// numbers, clears the address fields of the `Details` and shifts all coordinates by an
+/// offset derived from a secret. Distances and shapes are kept, so the flight can still be
+/// analyzed, but its location cannot be found without the secret.
+///
+/// Coordinates equal to `(0, 0)` are not shifted, as they mean that no position is known.
+/// In redacted log files, the content of experimental records holding positions is blanked, as
+/// their layout was not checked against captured logs.
+///
+/// # Example
+///
+/// ```ignore
+/// let redaction = Redaction::new("secret").with_serials(SerialRedaction::Hash);
+///
+/// // Redacted records and frames
+/// let mut frames = parser.frames(None)?;
+/// frames.iter_mut().for_each(|frame| redaction.apply_frame(frame));
+///
+/// // Redacted log file
+/// parser.redact(None, &redaction, File::create("redacted.txt")?)?;
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    serials: SerialRedaction,
+    secret: u64,
+    latitude_offset: f64,
+    longitude_offset: f64,
+    keep_images: bool,
+}
+
+impl Redaction {
+    /// Creates a redaction whose coordinate shift and serial number hashes derive from `secret`.
+    ///
+    /// The latitude is shifted by up to 10 degrees and the longitude by up to 180 degrees.
+    ///
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let secret = crc64(0, secret.as_ref());
+
+        let ratio = |bits: u64| (bits & 0xFFFF_FFFF) as f64 / u32::MAX as f64 * 2.0 - 1.0;
+
+        Redaction {
+            serials: SerialRedaction::default(),
+            secret,
+            latitude_offset: ratio(secret) * 10.0,
+            longitude_offset: ratio(secret >> 32) * 180.0,
+            keep_images: false,
+        }
+    }
+
+    /// Sets the coordinate shift, in degrees, instead of deriving it from the secret.
+    pub fn with_coordinate_shift(mut self, latitude_offset: f64, longitude_offset: f64) -> Self {
+        self.latitude_offset = latitude_offset;
+        self.longitude_offset = longitude_offset;
+        self
+    }
+
+    /// Sets how serial numbers are redacted.
+    pub fn with_serials(mut self, serials: SerialRedaction) -> Self {
+        self.serials = serials;
+        self
+    }
+
+    /// Keeps the `JPEG` records of redacted log files. Images are dropped by default, as
+    /// their EXIF data contains the location where they were taken.
+    pub fn keep_images(mut self, keep_images: bool) -> Self {
+        self.keep_images = keep_images;
+        self
+    }
+
+    /// Shifts a position given in degrees, returned as `(latitude, longitude)`.
+    ///
+    /// The latitude is clamped to the poles and the longitude wraps around the antimeridian.
+    ///
+    pub fn transform_coordinates(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        if latitude == 0.0 && longitude == 0.0 {
+            return (latitude, longitude);
+        }
+
+        let latitude = (latitude + self.latitude_offset).clamp(-90.0, 90.0);
+        let longitude = (longitude + self.longitude_offset + 180.0).rem_euclid(360.0) - 180.0;
+
+        (latitude, longitude)
+    }
+
+    /// Redacts a serial number. Empty serial numbers are kept empty.
+    pub fn redact_serial(&self, serial: &str) -> String {
+        self.redact_serial_with_width(serial, 16)
+    }
+
+    /// Redacts a serial number stored in a field of `width` bytes, hashing it to at most
+    /// `width` digits instead of truncating the hash.
+    fn redact_serial_with_width(&self, serial: &str, width: usize) -> String {
+        if serial.is_empty() {
+            return String::new();
+        }
+
+        match self.serials {
+            SerialRedaction::Blank => String::new(),
+            SerialRedaction::Hash => {
+                let width = width.min(16);
+                let hash = crc64(self.secret, serial.as_bytes());
+                format!("{:0width$}", hash % 10u64.pow(width as u32))
+            }
+        }
+    }
+
+    /// Redacts the log details: serial numbers, address, position and moment pics positions.
+    pub fn apply_details(&self, details: &mut Details) {
+        details.sub_street.clear();
+        details.street.clear();
+        details.city.clear();
+        details.area.clear();
+
+        (details.latitude, details.longitude) =
+            self.transform_coordinates(details.latitude, details.longitude);
+
+        for (latitude, longitude) in details
+            .moment_pic_latitude
+            .iter_mut()
+            .zip(details.moment_pic_longitude.iter_mut())
+        {
+            (*latitude, *longitude) = self.transform_coordinates(*latitude, *longitude);
+        }
+
+        details.aircraft_sn = self.redact_serial(&details.aircraft_sn);
+        details.camera_sn = self.redact_serial(&details.camera_sn);
+        details.rc_sn = self.redact_serial(&details.rc_sn);
+        details.battery_sn = self.redact_serial(&details.battery_sn);
+    }
+
+    /// Redacts a decoded record. Records without location nor serial numbers are left as is.
+    pub fn apply_record(&self, record: &mut Record) {
+        match record {
+            Record::OSD(osd) => {
+                (osd.latitude, osd.longitude) =
+                    self.transform_coordinates(osd.latitude, osd.longitude);
+            }
+            Record::Home(home) => {
+                (home.latitude, home.longitude) =
+                    self.transform_coordinates(home.latitude, home.longitude);
+            }
+            Record::AppGPS(app_gps) => {
+                (app_gps.latitude, app_gps.longitude) =
+                    self.transform_coordinates(app_gps.latitude, app_gps.longitude);
+            }
+            Record::RCGPS(rc_gps) => {
+                (rc_gps.latitude, rc_gps.longitude) =
+                    self.transform_rc_gps(rc_gps.latitude, rc_gps.longitude);
+            }
+            Record::Recover(recover) => {
+                recover.aircraft_sn = self.redact_serial(&recover.aircraft_sn);
+                recover.camera_sn = self.redact_serial(&recover.camera_sn);
+                recover.rc_sn = self.redact_serial(&recover.rc_sn);
+                recover.battery_sn = self.redact_serial(&recover.battery_sn);
+            }
+            Record::ComponentSerial(component_serial) => {
+                component_serial.serial = self.redact_serial(&component_serial.serial);
+            }
+            Record::ADSBFlightData(ADSBFlightData { aircraft })
+            | Record::ADSBFlightOriginal(ADSBFlightOriginal { aircraft }) => {
+                for aircraft in aircraft.iter_mut() {
+                    (aircraft.latitude, aircraft.longitude) =
+                        self.transform_coordinates(aircraft.latitude, aircraft.longitude);
+                }
+            }
+            Record::WaypointMissionUpload(mission) | Record::WaypointMissionDownload(mission) => {
+                (mission.poi_latitude, mission.poi_longitude) =
+                    self.transform_coordinates(mission.poi_latitude, mission.poi_longitude);
+            }
+            Record::WaypointUpload(waypoint) | Record::WaypointDownload(waypoint) => {
+                (waypoint.latitude, waypoint.longitude) =
+                    self.transform_coordinates(waypoint.latitude, waypoint.longitude);
+            }
+            _ => {}
+        }
+    }
+
+    /// Redacts a normalized frame.
+    pub fn apply_frame(&self, frame: &mut Frame) {
+        (frame.osd.latitude, frame.osd.longitude) =
+            self.transform_coordinates(frame.osd.latitude, frame.osd.longitude);
+        (frame.home.latitude, frame.home.longitude) =
+            self.transform_coordinates(frame.home.latitude, frame.home.longitude);
+        #[cfg(feature = "experimental-records")]
+        for traffic in frame.traffic.iter_mut() {
+            (traffic.latitude, traffic.longitude) =
+                self.transform_coordinates(traffic.latitude, traffic.longitude);
+        }
+
+        frame.recover.aircraft_sn = self.redact_serial(&frame.recover.aircraft_sn);
+        frame.recover.camera_sn = self.redact_serial(&frame.recover.camera_sn);
+        frame.recover.rc_sn = self.redact_serial(&frame.recover.rc_sn);
+        frame.recover.battery_sn = self.redact_serial(&frame.recover.battery_sn);
+    }
+
+    /// Shifts a `RCGPS` position, stored in 1e-7 degrees.
+    fn transform_rc_gps(&self, latitude: i32, longitude: i32) -> (i32, i32) {
+        let (latitude, longitude) =
+            self.transform_coordinates(latitude as f64 / 1e7, longitude as f64 / 1e7);
+        (
+            (latitude * 1e7).round() as i32,
+            (longitude * 1e7).round() as i32,
+        )
+    }
+
+    /// Redacts the plaintext content of a record, in place.
+    fn apply_payload(
+        &self,
+        type_id: u8,
+        version: u8,
+        product_type: ProductType,
+        payload: &mut Vec<u8>,
+    ) {
+        match type_id {
+            // OSD and Home, radians
+            1 | 2 => {
+                self.patch_coordinates(payload, 8, 0, |value| value.to_degrees(), f64::to_radians)
+            }
+            // AppGPS, degrees
+            14 => self.patch_coordinates(payload, 8, 0, |value| value, |value| value),
+            // RCGPS
+            11 if payload.len() >= 15 => {
+                let latitude = i32::from_le_bytes(payload[7..11].try_into().unwrap_or_default());
+                let longitude = i32::from_le_bytes(payload[11..15].try_into().unwrap_or_default());
+                let (latitude, longitude) = self.transform_rc_gps(latitude, longitude);
+                payload[7..11].copy_from_slice(&latitude.to_le_bytes());
+                payload[11..15].copy_from_slice(&longitude.to_le_bytes());
+            }
+            // ADSBFlightData and ADSBFlightOriginal hold aircraft positions at offsets which
+            // were not checked against captured logs, so their whole content is blanked
+            26 | 27 => payload.fill(0),
+            // WaypointMissionUpload, WaypointUpload, WaypointMissionDownload and WaypointDownload,
+            // whose positions are blanked along with the rest of the content for the same reason
+            35 | 36 | 38 | 39 => payload.fill(0),
+            // Recover
+            13 => {
+                let sn_size = if version <= 7 { 10 } else { 16 };
+                // product type, platform, app version, aircraft sn, aircraft name, timestamp
+                let aircraft_sn = 5;
+                let camera_sn = aircraft_sn + sn_size + 32 + 8;
+                let rc_sn = camera_sn + sn_size;
+                let battery_sn = rc_sn + sn_size;
+
+                for offset in [aircraft_sn, camera_sn, rc_sn] {
+                    self.patch_serial(payload, offset, sn_size);
+                }
+
+                let product_type = payload
+                    .first()
+                    .map(|value| ProductType::from(*value))
+                    .unwrap_or(product_type);
+                if let Some(bytes) = payload.get(battery_sn..battery_sn + sn_size) {
+                    let serial = parse_battery_sn(product_type, bytes.to_vec());
+                    let mut redacted = encode_battery_sn(
+                        product_type,
+                        &self.redact_serial_with_width(&serial, sn_size),
+                    );
+                    redacted.resize(sn_size, 0);
+                    payload[battery_sn..battery_sn + sn_size].copy_from_slice(&redacted);
+                }
+            }
+            // ComponentSerial, rewritten with the length of the redacted serial
+            40 if payload.len() >= 3 => {
+                let length = (payload[2] as usize).min(payload.len() - 3);
+                let serial = String::from_utf8_lossy(&payload[3..3 + length])
+                    .trim_end_matches('\0')
+                    .to_string();
+                let redacted = self.redact_serial(&serial).into_bytes();
+                payload.truncate(2);
+                payload.push(redacted.len() as u8);
+                payload.extend(redacted);
+            }
+            _ => {}
+        }
+    }
+
+    /// Shifts the `f64` coordinates found at the given offsets of `payload`.
+    fn patch_coordinates(
+        &self,
+        payload: &mut [u8],
+        latitude_offset: usize,
+        longitude_offset: usize,
+        to_degrees: impl Fn(f64) -> f64,
+        from_degrees: impl Fn(f64) -> f64,
+    ) {
+        let read = |payload: &[u8], offset: usize| {
+            payload
+                .get(offset..offset + 8)
+                .map(|bytes| f64::from_le_bytes(bytes.try_into().unwrap_or_default()))
+        };
+
+        if let (Some(latitude), Some(longitude)) = (
+            read(payload, latitude_offset),
+            read(payload, longitude_offset),
+        ) {
+            let (latitude, longitude) =
+                self.transform_coordinates(to_degrees(latitude), to_degrees(longitude));
+            payload[latitude_offset..latitude_offset + 8]
+                .copy_from_slice(&from_degrees(latitude).to_le_bytes());
+            payload[longitude_offset..longitude_offset + 8]
+                .copy_from_slice(&from_degrees(longitude).to_le_bytes());
+        }
+    }
+
+    /// Redacts the null padded serial number of `size` bytes found at `offset` of `payload`.
+    fn patch_serial(&self, payload: &mut [u8], offset: usize, size: usize) {
+        if let Some(bytes) = payload.get_mut(offset..offset + size) {
+            let serial = String::from_utf8_lossy(bytes)
+                .trim_end_matches('\0')
+                .to_string();
+            let mut redacted = self.redact_serial_with_width(&serial, size).into_bytes();
+            redacted.resize(size, 0);
+            bytes.copy_from_slice(&redacted);
+        }
+    }
+}
+
+/// Writes a redacted copy of `log` to `writer`.
+///
+/// Records are read one at a time, decoded to their plaintext content, redacted, then encoded
+/// again with the same keychains, so the copy can be read as the original log. Records that
+/// cannot be decoded are dropped.
+///
+/// Returns `Error::IncompleteRecords` if decoding stops before the end of the records section,
+/// so no record is silently left out of the copy.
+///
+pub(crate) fn redact_log<W: Write + Seek>(
+    log: &DJILog,
+    keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    redaction: &Redaction,
+    writer: W,
+) -> Result<W> {
+    let version = log.version;
+    let decryption_keychains = log.decryption_keychains(keychains.clone())?;
+
+    let mut details = log.details.clone();
+    redaction.apply_details(&mut details);
+
+    let mut log_writer = DJILogWriter::new(writer, version, details, keychains)?;
+    if version >= 13 {
+        let auxiliary_version = log.auxiliary_version()?;
+        log_writer = log_writer
+            .with_auxiliary_version(auxiliary_version.version, auxiliary_version.department);
+    }
+
+    let mut cursor = RecordCursor::new(log, decryption_keychains);
+    cursor.payloads = true;
+
+    while let Some(envelope) = cursor.next_envelope(log) {
+        match (envelope.record, envelope.payload) {
+            (Record::JPEG(jpeg), _) => {
+                if redaction.keep_images {
+                    log_writer.write_jpeg(&jpeg)?;
+                }
+            }
+            // KeyStorageRecover records are written as is
+            (_, Some(payload)) if envelope.type_id == KEY_STORAGE_RECOVER_TYPE_ID => {
+                log_writer.write_record(envelope.type_id, &payload)?;
+            }
+            (_, Some(mut payload)) => {
+                redaction.apply_payload(
+                    envelope.type_id,
+                    version,
+                    log.details.product_type,
+                    &mut payload,
+                );
+                log_writer.write_record(envelope.type_id, &payload)?;
+            }
+            (_, None) => {}
+        }
+    }
+
+    if cursor.report.unparsed_bytes > 0 {
+        if let Some(failure) = cursor.report.failures.pop() {
+            return Err(Error::IncompleteRecords {
+                offset: failure.offset,
+                error: Box::new(failure.error),
+            });
+        }
+    }
+
+    log_writer.finish()
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use crate::keychain::FeaturePoint;
+use crate::Error;
+
+/// Summary of a records decoding pass.
+///
+/// A `ParseReport` tells whether the records section of a log was fully decoded
+/// and, if not, which records failed and where and why decoding stopped. It allows to distinguish a
+/// truncated log from a record the parser is unable to decode.
+///
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    /// Byte offset of the records section start
+    pub records_offset: u64,
+    /// Byte offset of the records section end
+    pub records_end_offset: u64,
+    /// Records that could not be decoded
+    pub failures: Vec<ParseFailure>,
+    /// Regions skipped to resynchronize on the next record, in recovery mode
+    pub skipped_regions: Vec<SkippedRegion>,
+    /// Number of decoded `Record::Unknown` records
+    pub unknown_count: usize,
+    /// Number of decoded `Record::Invalid` records
+    pub invalid_count: usize,
+    /// Number of records skipped by a `RecordFilter`
+    pub filtered_count: usize,
+    /// Decryption statistics of each feature point met (versions >= 13)
+    pub decryption: HashMap<FeaturePoint, DecryptionStats>,
+    /// Number of bytes left unparsed before the end of the records section,
+    /// including skipped regions
+    pub unparsed_bytes: u64,
+}
+
+impl ParseReport {
+    /// Returns `true` if decoding reached the end of the records section.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty() && self.unparsed_bytes == 0
+    }
+
+    /// Returns the ratio of the records section that was decoded, between 0.0 and 1.0.
+    pub fn parsed_ratio(&self) -> f64 {
+        let length = self.records_end_offset.saturating_sub(self.records_offset);
+        if length == 0 {
+            return 1.0;
+        }
+        1.0 - (self.unparsed_bytes.min(length) as f64 / length as f64)
+    }
+
+    /// Returns the feature points with records that were not decrypted, either because
+    /// the keychain has no key for them or because decryption failed.
+    pub fn undecrypted_feature_points(&self) -> Vec<FeaturePoint> {
+        let mut feature_points: Vec<FeaturePoint> = self
+            .decryption
+            .iter()
+            .filter(|(_, stats)| !stats.is_complete())
+            .map(|(feature_point, _)| *feature_point)
+            .collect();
+        feature_points.sort_by_key(|feature_point| *feature_point as u16);
+        feature_points
+    }
+}
+
+/// Decryption statistics of the records of a feature point.
+///
+/// Records of a feature point missing from the keychain are only XOR decoded, so their
+/// content is unreliable even though they are parsed.
+///
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DecryptionStats {
+    /// Number of records seen
+    pub records: usize,
+    /// Number of records successfully decrypted
+    pub decrypted: usize,
+    /// Number of records parsed without decryption, as the keychain has no key for them
+    pub missing_key: usize,
+    /// Number of records with an invalid AES padding, usually decrypted with a wrong key
+    pub padding_errors: usize,
+    /// Number of records that could not be decrypted for another reason
+    pub other_errors: usize,
+}
+
+impl DecryptionStats {
+    /// Returns `true` if all the records seen were decrypted.
+    pub fn is_complete(&self) -> bool {
+        self.decrypted == self.records
+    }
+}
+
+/// A record that could not be decoded.
+#[derive(Debug)]
+pub struct ParseFailure {
+    /// Byte offset of the record in the log file
+    pub offset: u64,
+    /// Record type id, i.e. the first byte of the record
+    pub record_type: Option<u8>,
+    /// Underlying decoding error. Records which cannot be decrypted are reported with
+    /// `Error::InvalidKeyLength`, `Error::ShortCiphertext` or `Error::InvalidPadding`.
+    pub error: Error,
+}
+
+/// A region of the records section skipped while resynchronizing on the next record.
+#[derive(Debug, Clone, Copy)]
+pub struct SkippedRegion {
+    /// Byte offset of the region in the log file
+    pub offset: u64,
+    /// Length of the region in bytes
+    pub length: u64,
+}
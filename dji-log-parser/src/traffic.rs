@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::frame::Frame;
+use crate::record::adsb::ADSBWarningLevel;
+
+/// A manned aircraft detected by AirSense within a DJI log, along with its closest approach.
+///
+/// Encounters are built from the `traffic` of the log frames, one per ICAO address. The
+/// closest approach is the frame where the aircraft was at the smallest slant distance from
+/// the drone, combining horizontal distance and vertical separation.
+///
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficEncounter {
+    /// ICAO address of the aircraft, as 6 hexadecimal digits
+    pub icao_address: String,
+    /// Last call sign reported by the aircraft
+    pub call_sign: String,
+    /// Index of the first frame the aircraft was detected in, as returned by `DJILog::frames`
+    pub first_frame: usize,
+    /// Index of the last frame the aircraft was detected in
+    pub last_frame: usize,
+    /// Highest AirSense threat level reported for the aircraft
+    pub max_warning_level: Option<ADSBWarningLevel>,
+    /// Closest approach of the aircraft
+    pub closest_approach: ClosestApproach,
+}
+
+/// Position of an aircraft relative to the drone at its closest approach.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosestApproach {
+    /// Index of the frame of the closest approach
+    pub frame: usize,
+    /// Date and time of the closest approach
+    pub time: DateTime<Utc>,
+    /// Horizontal distance from the drone in meters
+    pub distance: f32,
+    /// Altitude difference with the drone in meters, positive when the aircraft is above
+    pub vertical_separation: f32,
+    /// Slant distance from the drone in meters
+    pub slant_distance: f32,
+}
+
+impl TrafficEncounter {
+    /// Collects the aircraft detected in a sequence of frames.
+    ///
+    /// Traffic without position, or detected while the drone has no position, is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames` - The frames of a log, in log order.
+    ///
+    /// # Returns
+    ///
+    /// This function returns one encounter per aircraft, closest first. Their frame indices
+    /// index `frames`.
+    ///
+    pub fn from_frames(frames: &[Frame]) -> Vec<TrafficEncounter> {
+        let mut encounters: Vec<TrafficEncounter> = Vec::new();
+
+        for (index, frame) in frames.iter().enumerate() {
+            if frame.osd.latitude == 0.0 && frame.osd.longitude == 0.0 {
+                continue;
+            }
+
+            for traffic in &frame.traffic {
+                if traffic.latitude == 0.0 && traffic.longitude == 0.0 {
+                    continue;
+                }
+
+                let approach = ClosestApproach {
+                    frame: index,
+                    time: frame.custom.date_time,
+                    distance: traffic.distance,
+                    vertical_separation: traffic.vertical_separation,
+                    slant_distance: traffic.distance.hypot(traffic.vertical_separation),
+                };
+
+                match encounters
+                    .iter_mut()
+                    .find(|encounter| encounter.icao_address == traffic.icao_address)
+                {
+                    Some(encounter) => {
+                        encounter.call_sign = traffic.call_sign.clone();
+                        encounter.last_frame = index;
+                        encounter.max_warning_level =
+                            encounter.max_warning_level.max(traffic.warning_level);
+                        if approach.slant_distance < encounter.closest_approach.slant_distance {
+                            encounter.closest_approach = approach;
+                        }
+                    }
+                    None => encounters.push(TrafficEncounter {
+                        icao_address: traffic.icao_address.clone(),
+                        call_sign: traffic.call_sign.clone(),
+                        first_frame: index,
+                        last_frame: index,
+                        max_warning_level: traffic.warning_level,
+                        closest_approach: approach,
+                    }),
+                }
+            }
+        }
+
+        encounters.sort_by(|a, b| {
+            a.closest_approach
+                .slant_distance
+                .total_cmp(&b.closest_approach.slant_distance)
+        });
+        encounters
+    }
+}
@@ -214,7 +214,7 @@ pub fn pad_with_zeros(input: &[u8], min_length: usize) -> Vec<u8> {
 /// otherwise the original message.
 ///
 ///
-pub fn append_message(original_message: String, message: impl Into<String>) -> String {
+pub fn append_message(original_message: &str, message: impl Into<String>) -> String {
     if !original_message.is_empty() {
         format!("{}; {}", original_message, message.into())
     } else {
@@ -0,0 +1,335 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::encoder::{record_encoder, xor_encode};
+use crate::keychain::{Keychain, KeychainFeaturePoint};
+use crate::layout::auxiliary::Department;
+use crate::layout::details::{Details, ProductType};
+use crate::layout::prefix::{INFO_SIZE, PREFIX_SIZE};
+use crate::record::{Record, RecordEnvelope, END_BYTE, KEY_STORAGE_RECOVER_TYPE_ID};
+use crate::{Error, Result};
+
+/// Writer building a DJI log file from a `Details` and a list of records.
+///
+/// The `Prefix`, the `Details` block (versions prior to 13) or the `Auxiliary` Info and
+/// Version blocks (versions 13 and later) are written as expected by `DJILog`. Records are
+/// given as their plaintext content, or as `RecordEnvelope` objects read from another log,
+/// and are XOR encoded (versions 7 and later), then AES encrypted when a keychain is
+/// provided (versions 13 and later).
+///
+/// Versions 6 and later are supported.
+///
+/// # Example
+///
+/// ```ignore
+/// let file = File::create("DJIFlightRecord.txt")?;
+/// let mut writer = DJILogWriter::new(file, 13, details, Some(keychains))?;
+/// writer.write_record(1, &osd_payload)?;
+/// writer.finish()?;
+/// ```
+///
+pub struct DJILogWriter<W: Write + Seek> {
+    writer: W,
+    version: u8,
+    details: Details,
+    auxiliary_version: u16,
+    department: Department,
+    keychains: VecDeque<Keychain>,
+    keychain: RefCell<Keychain>,
+    start_position: u64,
+    records_offset: Option<u64>,
+}
+
+impl<W: Write + Seek> DJILogWriter<W> {
+    /// Creates a writer for a log of the given version.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination of the log file.
+    /// * `version` - The log version to write.
+    /// * `details` - The log details.
+    /// * `keychains` - An optional vector of vectors containing `KeychainFeaturePoint` instances, used
+    ///   to encrypt records of logs versions >= 13. Keychains are switched each time a `KeyStorageRecover`
+    ///   record is written, as when reading. If `None` is provided, records are only XOR encoded.
+    ///
+    pub fn new(
+        mut writer: W,
+        version: u8,
+        details: Details,
+        keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+    ) -> Result<Self> {
+        if version < 6 {
+            return Err(Error::UnsupportedVersion(version));
+        }
+
+        let mut keychains: VecDeque<Keychain> = keychains
+            .unwrap_or_default()
+            .iter()
+            .map(Keychain::from_feature_points)
+            .collect();
+
+        let start_position = writer.stream_position()?;
+
+        Ok(DJILogWriter {
+            writer,
+            version,
+            details,
+            auxiliary_version: version as u16,
+            department: Department::DJIFly,
+            keychain: RefCell::new(keychains.pop_front().unwrap_or(Keychain::empty())),
+            keychains,
+            start_position,
+            records_offset: None,
+        })
+    }
+
+    /// Sets the version and department written in the `Auxiliary` Version block (versions >= 13),
+    /// used to request keychains from the DJI API.
+    pub fn with_auxiliary_version(mut self, version: u16, department: Department) -> Self {
+        self.auxiliary_version = version;
+        self.department = department;
+        self
+    }
+
+    /// Writes a record from its plaintext content.
+    ///
+    /// `KeyStorageRecover` records are written as is and switch to the next keychain.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_id` - The record type id, i.e. the first byte of the record.
+    /// * `payload` - The plaintext record content.
+    ///
+    pub fn write_record(&mut self, type_id: u8, payload: &[u8]) -> Result<()> {
+        self.write_header()?;
+
+        let content = if type_id == KEY_STORAGE_RECOVER_TYPE_ID {
+            payload.to_vec()
+        } else {
+            record_encoder(type_id, self.version, &self.keychain, payload)?
+        };
+
+        let too_long = Error::RecordTooLong {
+            version: self.version,
+            length: content.len(),
+        };
+
+        self.writer.write_all(&[type_id])?;
+        if self.version <= 12 {
+            let length = u8::try_from(content.len()).map_err(|_| too_long)?;
+            self.writer.write_all(&[length])?;
+        } else {
+            let length = u16::try_from(content.len()).map_err(|_| too_long)?;
+            self.writer.write_all(&length.to_le_bytes())?;
+        }
+        self.writer.write_all(&content)?;
+        self.writer.write_all(&[END_BYTE])?;
+
+        if type_id == KEY_STORAGE_RECOVER_TYPE_ID {
+            self.keychain = RefCell::new(self.keychains.pop_front().unwrap_or(Keychain::empty()));
+        }
+
+        Ok(())
+    }
+
+    /// Writes a record read from a log, from its plaintext content.
+    ///
+    /// Envelopes keep the plaintext content of their record when read with
+    /// `RecordIter::with_payloads`, so the records of a log can be written to another one,
+    /// for instance with other keychains or after editing their content.
+    ///
+    /// ```ignore
+    /// let mut writer = DJILogWriter::new(file, parser.version, parser.details.clone(), None)?;
+    /// for envelope in parser.records_iter(None)?.with_payloads().envelopes() {
+    ///     writer.write_envelope(&envelope)?;
+    /// }
+    /// writer.finish()?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::MissingPayload` if the envelope has no payload, as read without
+    /// `RecordIter::with_payloads` or for a `Record::Invalid` record without framing.
+    ///
+    pub fn write_envelope(&mut self, envelope: &RecordEnvelope) -> Result<()> {
+        match (&envelope.record, &envelope.payload) {
+            (Record::JPEG(jpeg), _) => self.write_jpeg(jpeg),
+            (_, Some(payload)) => self.write_record(envelope.type_id, payload),
+            (_, None) => Err(Error::MissingPayload(envelope.offset)),
+        }
+    }
+
+    /// Writes a JPEG record. JPEG records are neither encoded nor encrypted.
+    pub fn write_jpeg(&mut self, jpeg: &[u8]) -> Result<()> {
+        self.write_header()?;
+        self.writer.write_all(jpeg)?;
+        Ok(())
+    }
+
+    /// Writes the trailing `Details` block (versions prior to 12) and completes the `Prefix`.
+    ///
+    /// # Returns
+    ///
+    /// This function returns `Result<W>`. On success, it returns the inner writer.
+    ///
+    pub fn finish(mut self) -> Result<W> {
+        self.write_header()?;
+
+        let end_position = self.writer.stream_position()? - self.start_position;
+
+        let (detail_offset, detail_length) = match self.version {
+            0..=11 => {
+                self.writer.write_all(&encode_details(&self.details))?;
+                (end_position, INFO_SIZE as u16)
+            }
+            12 => (PREFIX_SIZE, INFO_SIZE as u16),
+            _ => {
+                let records_offset = self.records_offset.unwrap_or(PREFIX_SIZE);
+                (records_offset, (records_offset - PREFIX_SIZE) as u16)
+            }
+        };
+
+        let end = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(self.start_position))?;
+        self.writer
+            .write_all(&encode_prefix(detail_offset, detail_length, self.version))?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    /// Writes everything preceding the records, once.
+    fn write_header(&mut self) -> Result<()> {
+        if self.records_offset.is_some() {
+            return Ok(());
+        }
+
+        // Completed by `finish`
+        self.writer.write_all(&[0u8; PREFIX_SIZE as usize])?;
+
+        if self.version == 12 {
+            self.writer.write_all(&encode_details(&self.details))?;
+        } else if self.version >= 13 {
+            // Info block
+            let details = encode_details(&self.details);
+            let mut info = vec![1u8];
+            info.extend((details.len() as u16).to_le_bytes());
+            info.extend(details);
+            info.extend(0u16.to_le_bytes()); // no signature
+            let info = xor_encode(0, &info);
+
+            self.writer.write_all(&[0u8])?;
+            self.writer.write_all(&(info.len() as u16).to_le_bytes())?;
+            self.writer.write_all(&info)?;
+
+            // Version block
+            self.writer.write_all(&[1u8])?;
+            self.writer.write_all(&3u16.to_le_bytes())?;
+            self.writer
+                .write_all(&self.auxiliary_version.to_le_bytes())?;
+            self.writer.write_all(&[self.department.clone().into()])?;
+        }
+
+        self.records_offset = Some(self.writer.stream_position()? - self.start_position);
+
+        Ok(())
+    }
+}
+
+/// Encodes the `Prefix` block.
+fn encode_prefix(detail_offset: u64, detail_length: u16, version: u8) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(PREFIX_SIZE as usize);
+    buffer.extend(detail_offset.to_le_bytes());
+    buffer.extend(detail_length.to_le_bytes());
+    buffer.push(version);
+    buffer.resize(PREFIX_SIZE as usize, 0);
+    buffer
+}
+
+/// Encodes the `Details` block, using the layout of versions 6 and later.
+fn encode_details(details: &Details) -> Vec<u8> {
+    fn push_str(buffer: &mut Vec<u8>, value: &str, count: usize) {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(count, 0);
+        buffer.extend(bytes);
+    }
+
+    let mut buffer = Vec::with_capacity(INFO_SIZE as usize);
+
+    push_str(&mut buffer, &details.sub_street, 20);
+    push_str(&mut buffer, &details.street, 20);
+    push_str(&mut buffer, &details.city, 20);
+    push_str(&mut buffer, &details.area, 20);
+    buffer.push(details.is_favorite);
+    buffer.push(details.is_new);
+    buffer.push(details.needs_upload);
+    buffer.extend(details.record_line_count.to_le_bytes());
+    buffer.extend(details.detail_info_checksum.to_le_bytes());
+    buffer.extend(details.start_time.timestamp_millis().to_le_bytes());
+    buffer.extend(details.longitude.to_le_bytes());
+    buffer.extend(details.latitude.to_le_bytes());
+    buffer.extend(details.total_distance.to_le_bytes());
+    buffer.extend(((details.total_time * 1000.0).round() as i32).to_le_bytes());
+    buffer.extend(details.max_height.to_le_bytes());
+    buffer.extend(details.max_horizontal_speed.to_le_bytes());
+    buffer.extend(details.max_vertical_speed.to_le_bytes());
+    buffer.extend(details.capture_num.to_le_bytes());
+    buffer.extend(details.video_time.to_le_bytes());
+    for value in details.moment_pic_image_buffer_len {
+        buffer.extend(value.to_le_bytes());
+    }
+    for value in details.moment_pic_shrink_image_buffer_len {
+        buffer.extend(value.to_le_bytes());
+    }
+    for value in details.moment_pic_longitude {
+        buffer.extend(value.to_radians().to_le_bytes());
+    }
+    for value in details.moment_pic_latitude {
+        buffer.extend(value.to_radians().to_le_bytes());
+    }
+    buffer.extend([0u8; 8]); // analysis offset
+    buffer.extend([0u8; 16]); // user api center id md5
+    buffer.extend(details.take_off_altitude.to_le_bytes());
+    buffer.push(details.product_type.into());
+    buffer.extend([0u8; 8]); // activation timestamp
+    push_str(&mut buffer, &details.aircraft_name, 32);
+    push_str(&mut buffer, &details.aircraft_sn, 16);
+    push_str(&mut buffer, &details.camera_sn, 16);
+    push_str(&mut buffer, &details.rc_sn, 16);
+    buffer.extend(encode_battery_sn(details.product_type, &details.battery_sn));
+    buffer.push(details.app_platform.clone().into());
+
+    let mut app_version = details
+        .app_version
+        .split('.')
+        .map(|part| part.parse::<u8>().unwrap_or_default());
+    for _ in 0..3 {
+        buffer.push(app_version.next().unwrap_or_default());
+    }
+
+    buffer.resize(INFO_SIZE as usize, 0);
+    buffer
+}
+
+/// Encodes the battery serial number, reversing `details::parse_battery_sn`.
+pub(crate) fn encode_battery_sn(product_type: ProductType, battery_sn: &str) -> Vec<u8> {
+    const BCD_PRODUCTS: [ProductType; 3] = [
+        ProductType::Inspire1,
+        ProductType::Inspire1Pro,
+        ProductType::Inspire1RAW,
+    ];
+
+    let mut bytes: Vec<u8> = if BCD_PRODUCTS.contains(&product_type) {
+        battery_sn
+            .bytes()
+            .rev()
+            .map(|digit| digit.wrapping_sub(b'0') & 0xF)
+            .collect()
+    } else {
+        battery_sn.as_bytes().to_vec()
+    };
+    bytes.resize(16, 0);
+    bytes
+}
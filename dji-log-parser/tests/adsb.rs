@@ -0,0 +1,143 @@
+//! ADS-B records, as laid out in `record::adsb`, and the traffic of frames.
+#![cfg(feature = "experimental-records")]
+
+use std::io::Cursor;
+
+use dji_log_parser::record::adsb::{ADSBAircraft, ADSBWarningLevel};
+use dji_log_parser::record::Record;
+use dji_log_parser::{DJILog, DJILogWriter};
+
+mod common;
+
+use common::{details, osd_payload};
+
+/// ADS-B aircraft entry, 31 bytes.
+fn aircraft(icao_address: u32, call_sign: &[u8; 8], warning_level: u8) -> Vec<u8> {
+    let mut entry = icao_address.to_le_bytes().to_vec();
+    entry.extend(call_sign);
+    entry.extend(463_012_345i32.to_le_bytes());
+    entry.extend((-61_234_567i32).to_le_bytes());
+    entry.extend(1_524_500i32.to_le_bytes());
+    entry.extend(27_050u16.to_le_bytes());
+    entry.extend(6_425u16.to_le_bytes());
+    entry.extend((-512i16).to_le_bytes());
+    entry.push(warning_level);
+    assert_eq!(entry.len(), 31);
+    entry
+}
+
+fn assert_aircraft(aircraft: &ADSBAircraft) {
+    assert_eq!(aircraft.latitude, 46.3012345);
+    assert_eq!(aircraft.longitude, -6.1234567);
+    assert_eq!(aircraft.altitude, 1524.5);
+    assert_eq!(aircraft.heading, 270.5);
+    assert_eq!(aircraft.horizontal_speed, 64.25);
+    assert_eq!(aircraft.vertical_speed, -5.12);
+}
+
+#[test]
+fn adsb_records_are_decoded() {
+    for version in [6, 12] {
+        let mut flight_data = vec![2];
+        flight_data.extend(aircraft(0x4B1A2C, b"SWR123\0\0", 3));
+        flight_data.extend(aircraft(0xFF3C4D5E, b"HBZAB   ", 0));
+
+        let mut flight_original = vec![1];
+        flight_original.extend(aircraft(0x4B1A2C, b"SWR123\0\0", 7));
+
+        let mut writer =
+            DJILogWriter::new(Cursor::new(Vec::new()), version, details(), None).unwrap();
+        writer.write_record(26, &flight_data).unwrap();
+        writer.write_record(27, &flight_original).unwrap();
+        writer.write_record(26, &[0]).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let records = DJILog::from_bytes(bytes).unwrap().records(None).unwrap();
+
+        let Record::ADSBFlightData(flight_data) = &records[0] else {
+            panic!("version {}: {:?}", version, records[0]);
+        };
+        assert_eq!(flight_data.aircraft.len(), 2);
+        for aircraft in &flight_data.aircraft {
+            assert_aircraft(aircraft);
+        }
+        assert_eq!(flight_data.aircraft[0].icao_hex(), "4B1A2C");
+        assert_eq!(flight_data.aircraft[0].call_sign, "SWR123");
+        assert_eq!(
+            flight_data.aircraft[0].warning_level,
+            ADSBWarningLevel::High
+        );
+        // Only the 24 low bits are the ICAO address, padding spaces are trimmed
+        assert_eq!(flight_data.aircraft[1].icao_hex(), "3C4D5E");
+        assert_eq!(flight_data.aircraft[1].call_sign, "HBZAB");
+        assert_eq!(
+            flight_data.aircraft[1].warning_level,
+            ADSBWarningLevel::None
+        );
+
+        let Record::ADSBFlightOriginal(flight_original) = &records[1] else {
+            panic!("version {}: {:?}", version, records[1]);
+        };
+        assert_eq!(flight_original.aircraft.len(), 1);
+        assert_aircraft(&flight_original.aircraft[0]);
+        assert_eq!(
+            flight_original.aircraft[0].warning_level,
+            ADSBWarningLevel::Unknown(7)
+        );
+
+        // No aircraft in range
+        let Record::ADSBFlightData(flight_data) = &records[2] else {
+            panic!("version {}: {:?}", version, records[2]);
+        };
+        assert!(flight_data.aircraft.is_empty());
+    }
+}
+
+/// ADSBFlightData payload with one aircraft at a position in degrees.
+fn adsb_payload(latitude: f64, longitude: f64) -> Vec<u8> {
+    let mut payload = vec![1u8];
+    payload.extend(0xA1B2C3u32.to_le_bytes());
+    payload.extend(b"TEST123\0");
+    payload.extend(((latitude * 1e7) as i32).to_le_bytes());
+    payload.extend(((longitude * 1e7) as i32).to_le_bytes());
+    payload.extend(150_000i32.to_le_bytes());
+    payload.extend([0; 6]);
+    payload.push(2);
+    payload
+}
+
+#[test]
+fn stale_traffic_is_dropped() {
+    let mut writer = DJILogWriter::new(Cursor::new(Vec::new()), 6, details(), None).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 1.0))
+        .unwrap();
+    writer.write_record(26, &adsb_payload(46.01, 6.0)).unwrap();
+    // The drone flies towards the aircraft position long after the ADS-B record
+    for fly_time in 2..=20 {
+        let latitude = 46.0 + 0.0005 * fly_time as f64;
+        writer
+            .write_record(1, &osd_payload(latitude, 6.0, fly_time as f32))
+            .unwrap();
+    }
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let log = DJILog::from_bytes(bytes).unwrap();
+    let frames = log.frames(None).unwrap();
+
+    assert_eq!(frames[0].traffic[0].icao_address, "A1B2C3");
+    assert_eq!(frames[0].traffic[0].call_sign, "TEST123");
+    let last_frame_with_traffic = frames
+        .iter()
+        .rposition(|frame| !frame.traffic.is_empty())
+        .unwrap();
+    assert_eq!(frames[last_frame_with_traffic].osd.fly_time, 6.0);
+
+    let encounters = log.traffic(None).unwrap();
+    assert_eq!(encounters.len(), 1);
+    assert_eq!(encounters[0].last_frame, last_frame_with_traffic);
+    assert_eq!(
+        encounters[0].closest_approach.frame,
+        last_frame_with_traffic
+    );
+}
@@ -0,0 +1,135 @@
+//! Helpers shared by the integration tests, building logs with `DJILogWriter`.
+#![allow(dead_code)]
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use dji_log_parser::keychain::{FeaturePoint, KeychainFeaturePoint};
+use dji_log_parser::layout::details::Details;
+use dji_log_parser::{DJILog, DJILogWriter};
+
+pub const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+pub const IV: &str = "AAECAwQFBgcICQoLDA0ODw==";
+pub const OTHER_KEY: &str = "HxweHRwbGhkYFxYVFBMSERAPDg0MCwoJCAcGBQQDAgE=";
+
+/// Xorshift generator, so the corpus is the same on every run.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound.max(1) as u64) as usize
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Vec<u8> {
+        (0..count).map(|_| self.next() as u8).collect()
+    }
+}
+
+pub fn keychains(key: &str, iv: &str) -> Vec<Vec<KeychainFeaturePoint>> {
+    let keychain = [
+        FeaturePoint::BaseFeature,
+        FeaturePoint::GimbalFeature,
+        FeaturePoint::RCFeature,
+        FeaturePoint::BatteryFeature,
+        FeaturePoint::CameraFeature,
+    ]
+    .into_iter()
+    .map(|feature_point| KeychainFeaturePoint {
+        feature_point,
+        aes_key: key.into(),
+        aes_iv: iv.into(),
+    })
+    .collect::<Vec<_>>();
+
+    vec![keychain.clone(), keychain]
+}
+
+/// Minimal version 6 log, used as the source of the details of the other logs.
+pub fn v6_log() -> Vec<u8> {
+    let records = [1u8, 3, 0, 0, 0, 0xFF];
+
+    let mut bytes = Vec::new();
+    bytes.extend((100 + records.len() as u64).to_le_bytes());
+    bytes.extend(436u16.to_le_bytes());
+    bytes.push(6);
+    bytes.resize(100, 0);
+    bytes.extend(records);
+    bytes.resize(bytes.len() + 436, 0);
+    bytes
+}
+
+/// Log of `version` with records of various types, including unknown and JPEG records.
+pub fn write_log(version: u8, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) -> Vec<u8> {
+    let details = DJILog::from_bytes(v6_log()).unwrap().details;
+    let mut writer =
+        DJILogWriter::new(Cursor::new(Vec::new()), version, details, keychains).unwrap();
+
+    let mut rng = Rng(u64::from(version) + 1);
+    for (type_id, length) in [
+        (1, 53),
+        (2, 40),
+        (3, 20),
+        (5, 30),
+        (7, 60),
+        (50, 12),
+        (1, 53),
+        (99, 10),
+    ] {
+        writer.write_record(type_id, &rng.bytes(length)).unwrap();
+    }
+    writer
+        .write_jpeg(&[0xFF, 0xD8, 0x01, 0x02, 0xFF, 0xD9])
+        .unwrap();
+    writer.write_record(1, &rng.bytes(53)).unwrap();
+
+    writer.finish().unwrap().into_inner()
+}
+
+/// Details of `v6_log`, to build other logs with `DJILogWriter`.
+pub fn details() -> Details {
+    DJILog::from_bytes(v6_log()).unwrap().details
+}
+
+/// OSD record payload at a position in degrees, `fly_time` seconds after takeoff.
+pub fn osd_payload(latitude: f64, longitude: f64, fly_time: f32) -> Vec<u8> {
+    let mut payload = vec![0u8; 53];
+    payload[0..8].copy_from_slice(&longitude.to_radians().to_le_bytes());
+    payload[8..16].copy_from_slice(&latitude.to_radians().to_le_bytes());
+    payload[42..44].copy_from_slice(&((fly_time * 10.0) as u16).to_le_bytes());
+    payload
+}
+
+/// Version 13 log with a `KeyStorage` record holding `ciphertext`, followed by records
+/// encrypted with `keychains`.
+pub fn key_storage_log(ciphertext: &[u8], keychains: Vec<Vec<KeychainFeaturePoint>>) -> Vec<u8> {
+    let mut writer =
+        DJILogWriter::new(Cursor::new(Vec::new()), 13, details(), Some(keychains)).unwrap();
+
+    // KeyStorage records hold a feature point, a length and the encrypted keychain entry
+    let mut key_storage = vec![1, 0];
+    key_storage.extend((ciphertext.len() as u16).to_le_bytes());
+    key_storage.extend(ciphertext);
+    writer.write_record(56, &key_storage).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 1.0))
+        .unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 2.0))
+        .unwrap();
+
+    writer.finish().unwrap().into_inner()
+}
+
+/// Empty directory under the system temporary directory, unique to the test process.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dji-log-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    path
+}
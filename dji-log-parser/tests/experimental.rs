@@ -0,0 +1,45 @@
+//! Experimental records, decoded into `Record::Unknown` unless the `experimental-records`
+//! feature is enabled.
+#![cfg(not(feature = "experimental-records"))]
+
+use std::io::Cursor;
+
+use dji_log_parser::record::Record;
+use dji_log_parser::{DJILog, DJILogWriter};
+
+mod common;
+
+use common::{details, keychains, IV, KEY};
+
+#[test]
+fn experimental_records_are_unknown() {
+    let type_ids = [17, 18, 26, 27, 32, 35, 36, 38, 39];
+
+    for (version, keychains) in [(6, None), (13, Some(keychains(KEY, IV)))] {
+        let mut writer = DJILogWriter::new(
+            Cursor::new(Vec::new()),
+            version,
+            details(),
+            keychains.clone(),
+        )
+        .unwrap();
+        for type_id in type_ids {
+            writer.write_record(type_id, &[1, 2, 3, 4, 5]).unwrap();
+        }
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let records = DJILog::from_bytes(bytes)
+            .unwrap()
+            .records(keychains)
+            .unwrap();
+        assert_eq!(records.len(), type_ids.len());
+        for (record, type_id) in records.iter().zip(type_ids) {
+            assert!(
+                matches!(record, Record::Unknown(id, data) if *id == type_id && data == &[1, 2, 3, 4, 5]),
+                "version {}: {:?}",
+                version,
+                record
+            );
+        }
+    }
+}
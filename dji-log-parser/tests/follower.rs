@@ -0,0 +1,68 @@
+//! Live tail of a log being written with `DJILogFollower`.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use dji_log_parser::{DJILog, DJILogFollower};
+
+mod common;
+
+use common::{keychains, write_log, IV, KEY};
+
+#[test]
+fn partial_records_are_held_back() {
+    let bytes = write_log(13, Some(keychains(KEY, IV)));
+    let log = DJILog::from_bytes(bytes.clone()).unwrap();
+    let expected: Vec<String> = log
+        .records(Some(keychains(KEY, IV)))
+        .unwrap()
+        .iter()
+        .map(|record| format!("{:?}", record))
+        .collect();
+    let ends: Vec<usize> = log
+        .record_envelopes(Some(keychains(KEY, IV)))
+        .unwrap()
+        .iter()
+        .map(|envelope| (envelope.offset + envelope.length) as usize)
+        .collect();
+
+    let path = std::env::temp_dir().join(format!("dji-log-follower-{}.txt", std::process::id()));
+    let append = |range: std::ops::Range<usize>| {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&bytes[range]).unwrap();
+    };
+
+    // Two complete records, then half of the third one
+    let cut = ends[1] + (ends[2] - ends[1]) / 2;
+    append(0..cut);
+    let mut follower = DJILogFollower::open(&path, Some(keychains(KEY, IV))).unwrap();
+    let mut records = Vec::new();
+
+    let update = follower.poll().unwrap();
+    assert_eq!(update.records.len(), 2);
+    records.extend(update.records);
+
+    // Nothing was appended, the partial record is still held back
+    assert!(follower.poll().unwrap().records.is_empty());
+
+    // The end of the third record, then one byte of the fourth one
+    append(cut..ends[2] + 1);
+    let update = follower.poll().unwrap();
+    assert_eq!(update.records.len(), 1);
+    records.extend(update.records);
+
+    append(ends[2] + 1..bytes.len());
+    records.extend(follower.poll().unwrap().records);
+    fs::remove_file(&path).unwrap();
+
+    let records: Vec<String> = records
+        .iter()
+        .map(|record| format!("{:?}", record))
+        .collect();
+    assert_eq!(records, expected);
+    assert!(follower.report().failures.is_empty());
+}
@@ -0,0 +1,68 @@
+//! Normalization of records into frames, on logs built with `DJILogWriter`.
+
+use std::io::Cursor;
+
+use dji_log_parser::{DJILog, DJILogWriter, RecordFilter};
+
+mod common;
+
+use common::{details, osd_payload};
+
+/// Log with app operations and a joystick record in its first frame.
+fn app_log() -> Vec<u8> {
+    let mut writer = DJILogWriter::new(Cursor::new(Vec::new()), 6, details(), None).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 1.0))
+        .unwrap();
+    writer.write_record(20, &[4, 0]).unwrap();
+    writer.write_record(20, &[6, 2]).unwrap();
+    // Enabled, roll 0.5, pitch -0.5, yaw 0, throttle 1
+    writer
+        .write_record(29, &[1, 0xF4, 0x01, 0x0C, 0xFE, 0, 0, 0xE8, 0x03])
+        .unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 2.0))
+        .unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 3.0))
+        .unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn app_operations_and_joystick_are_normalized() {
+    let frames = DJILog::from_bytes(app_log()).unwrap().frames(None).unwrap();
+    let operations = &frames[0].app.operations;
+    assert_eq!(operations.len(), 2);
+    assert_eq!((operations[0].operation, operations[0].value), (4, 0));
+    assert_eq!((operations[1].operation, operations[1].value), (6, 2));
+
+    let joystick = &frames[0].app.joystick;
+    assert!(joystick.is_enabled);
+    assert_eq!(
+        (
+            joystick.roll,
+            joystick.pitch,
+            joystick.yaw,
+            joystick.throttle
+        ),
+        (0.5, -0.5, 0.0, 1.0)
+    );
+
+    // Operations are events of a single frame, the joystick position is kept
+    assert!(frames[1].app.operations.is_empty());
+    assert!(frames[1].app.joystick.is_enabled);
+}
+
+#[test]
+fn frames_filter_keeps_app_records() {
+    let log = DJILog::from_bytes(app_log()).unwrap();
+    let frames = log.frames(None).unwrap();
+    let filtered_frames = log
+        .frames_with_filter(None, RecordFilter::frames())
+        .unwrap();
+    assert_eq!(
+        serde_json::to_string(&filtered_frames).unwrap(),
+        serde_json::to_string(&frames).unwrap()
+    );
+}
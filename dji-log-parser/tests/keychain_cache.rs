@@ -0,0 +1,82 @@
+//! Keychains cached on disk with `FsKeychainCache`.
+
+use std::fs;
+
+use dji_log_parser::keychain::{FsKeychainCache, KeychainCache, Keychains, KeychainsRequest};
+use dji_log_parser::DJILog;
+
+mod common;
+
+use common::{key_storage_log, keychains, temp_dir, IV, KEY, OTHER_KEY};
+
+fn request(ciphertext: &[u8]) -> KeychainsRequest {
+    DJILog::from_bytes(key_storage_log(ciphertext, keychains(KEY, IV)))
+        .unwrap()
+        .keychains_request()
+        .unwrap()
+}
+
+#[test]
+fn entries_round_trip() {
+    let directory = temp_dir("cache-round-trip");
+    let cache = FsKeychainCache::new(&directory);
+    let request = request(&[1, 2, 3, 4]);
+    let key = request.cache_key().unwrap();
+
+    // The directory is created on first write
+    assert!(cache.get(&key).unwrap().is_none());
+    assert!(!directory.exists());
+
+    cache
+        .put(&key, &Keychains::new(&request, keychains(KEY, IV)))
+        .unwrap();
+    let entry = cache.get(&key).unwrap().unwrap();
+    assert_eq!(entry.fingerprint, request.fingerprint());
+    assert_eq!(entry.keychains.len(), 2);
+    assert_eq!(entry.keychains[0][0].aes_key, KEY);
+
+    // Entries are replaced
+    cache
+        .put(&key, &Keychains::new(&request, keychains(OTHER_KEY, IV)))
+        .unwrap();
+    assert_eq!(
+        cache.get(&key).unwrap().unwrap().keychains[0][0].aes_key,
+        OTHER_KEY
+    );
+    assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn cache_keys_are_stable() {
+    let request = request(&[1, 2, 3, 4]);
+
+    // Keys of existing caches must not change
+    assert_eq!(
+        KeychainsRequest::default().cache_key().unwrap(),
+        "4a15e7e6a3829235"
+    );
+    assert_eq!(
+        request.cache_key().unwrap(),
+        request.clone().cache_key().unwrap()
+    );
+    assert_eq!(
+        request.cache_key().unwrap(),
+        self::request(&[1, 2, 3, 4]).cache_key().unwrap()
+    );
+
+    // Another log, department or version is another entry
+    let other_log = self::request(&[4, 3, 2, 1]);
+    let other_department = KeychainsRequest {
+        department: request.department + 1,
+        ..request.clone()
+    };
+    let other_version = KeychainsRequest {
+        version: request.version + 1,
+        ..request.clone()
+    };
+    for other in [other_log, other_department, other_version] {
+        assert_ne!(other.cache_key().unwrap(), request.cache_key().unwrap());
+    }
+}
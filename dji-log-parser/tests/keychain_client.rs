@@ -0,0 +1,265 @@
+//! `KeychainClient` requests against a local mock of the DJI keychains API.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use dji_log_parser::keychain::{FeaturePoint, KeychainClient, KeychainsRequest};
+use dji_log_parser::Error;
+
+const KEYCHAINS: &str =
+    r#"[[{"featurePoint":"FR_Standardization_Feature_Base_1","aesKey":"a2V5","aesIv":"aXY="}]]"#;
+
+/// Reply of the mock server to a request.
+enum Reply {
+    /// Responds with a status and a JSON body
+    Json(u16, String),
+    /// Never responds, until the client gives up
+    Hang,
+}
+
+fn result(code: u8, data: &str) -> Reply {
+    Reply::Json(
+        200,
+        format!(
+            r#"{{"data":{},"result":{{"code":{},"msg":"message"}}}}"#,
+            data, code
+        ),
+    )
+}
+
+/// Serves one connection per reply, returning the raw requests received.
+fn serve(replies: Vec<Reply>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for reply in replies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            requests.push(request);
+
+            match reply {
+                Reply::Json(status, body) => {
+                    let response = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+                Reply::Hang => thread::sleep(Duration::from_millis(500)),
+            }
+        }
+        requests
+    });
+
+    (address, handle)
+}
+
+fn request() -> KeychainsRequest {
+    KeychainsRequest {
+        version: 13,
+        department: 3,
+        keychains: Vec::new(),
+    }
+}
+
+fn client(endpoint: &str) -> KeychainClient {
+    KeychainClient::builder()
+        .endpoint(endpoint)
+        .backoff(Duration::from_millis(10))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn fetches_keychains() {
+    let (address, server) = serve(vec![result(0, KEYCHAINS)]);
+
+    let client = KeychainClient::builder()
+        .endpoint(format!("{}/keychains", address))
+        .user_agent("mock-agent")
+        .build()
+        .unwrap();
+    let keychains = client.fetch("secret", &request()).unwrap();
+
+    assert_eq!(keychains.len(), 1);
+    assert_eq!(keychains[0][0].feature_point, FeaturePoint::BaseFeature);
+    assert_eq!(keychains[0][0].aes_key, "a2V5");
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST /keychains HTTP/1.1"));
+    assert!(requests[0].contains("Api-Key: secret"));
+    assert!(requests[0].contains("User-Agent: mock-agent"));
+    assert!(requests[0].ends_with(r#"{"version":13,"department":3,"keychainsArray":[]}"#));
+}
+
+#[test]
+fn result_codes_are_typed() {
+    let (address, server) = serve(vec![
+        result(1, "null"),
+        result(2, "null"),
+        result(3, "null"),
+        result(42, "null"),
+        result(0, "null"),
+    ]);
+    let client = client(&address);
+
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiKeyError)
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiQuotaExceeded)
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiUnsupportedVersion(13))
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiError(message)) if message == "message (code 42)"
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiError(_))
+    ));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn http_statuses_are_typed() {
+    let (address, server) = serve(vec![
+        Reply::Json(401, String::new()),
+        Reply::Json(403, String::new()),
+        Reply::Json(404, String::new()),
+        Reply::Json(429, String::new()),
+        Reply::Json(200, "not json".into()),
+    ]);
+    let client = KeychainClient::builder()
+        .endpoint(&address)
+        .retries(0)
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiKeyError)
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiKeyError)
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::NetworkRequestStatus(404))
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiQuotaExceeded)
+    ));
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::ApiError(_))
+    ));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn retries_transient_failures() {
+    let (address, server) = serve(vec![
+        Reply::Json(503, String::new()),
+        Reply::Json(429, String::new()),
+        result(0, KEYCHAINS),
+    ]);
+
+    let keychains = client(&address).fetch("secret", &request()).unwrap();
+    assert_eq!(keychains.len(), 1);
+    assert_eq!(server.join().unwrap().len(), 3);
+
+    let (address, server) = serve(vec![
+        Reply::Json(500, String::new()),
+        Reply::Json(502, String::new()),
+        Reply::Json(503, String::new()),
+        Reply::Json(400, String::new()),
+        result(0, KEYCHAINS),
+    ]);
+    let client = client(&address);
+
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::NetworkRequestStatus(503))
+    ));
+    // Client errors are not retried
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::NetworkRequestStatus(400))
+    ));
+    assert!(client.fetch("secret", &request()).is_ok());
+
+    server.join().unwrap();
+}
+
+#[test]
+fn times_out() {
+    let (address, server) = serve(vec![Reply::Hang]);
+
+    let client = KeychainClient::builder()
+        .endpoint(&address)
+        .timeout(Duration::from_millis(100))
+        .retries(0)
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        client.fetch("secret", &request()),
+        Err(Error::NetworkTimeout)
+    ));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn sends_requests_through_proxy() {
+    let (address, server) = serve(vec![result(0, KEYCHAINS)]);
+
+    let client = KeychainClient::builder()
+        .endpoint("http://keychains.invalid/keychains")
+        .proxy(&address)
+        .build()
+        .unwrap();
+    assert!(client.fetch("secret", &request()).is_ok());
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST http://keychains.invalid/keychains HTTP/1.1"));
+
+    assert!(matches!(
+        KeychainClient::builder()
+            .proxy("ftp://proxy.invalid")
+            .build(),
+        Err(Error::InvalidProxy(_))
+    ));
+}
@@ -0,0 +1,118 @@
+//! Keychains stored in a JSON sidecar file along with their log.
+
+use std::fs;
+
+use dji_log_parser::keychain::{Keychains, KEYCHAINS_FORMAT};
+use dji_log_parser::record::Record;
+use dji_log_parser::{DJILog, Error};
+
+mod common;
+
+use common::{key_storage_log, keychains, temp_dir, IV, KEY};
+
+fn log_keychains(log: &DJILog) -> Keychains {
+    Keychains::new(&log.keychains_request().unwrap(), keychains(KEY, IV))
+}
+
+#[test]
+fn sidecar_files_round_trip() {
+    let directory = temp_dir("sidecar-round-trip");
+    fs::create_dir_all(&directory).unwrap();
+    let log_path = directory.join("DJIFlightRecord.txt");
+    fs::write(
+        &log_path,
+        key_storage_log(&[1, 2, 3, 4], keychains(KEY, IV)),
+    )
+    .unwrap();
+
+    let log = DJILog::open(&log_path).unwrap();
+    let keychains = log_keychains(&log);
+    let sidecar_path = Keychains::sidecar_path(&log_path);
+    assert_eq!(
+        sidecar_path,
+        directory.join("DJIFlightRecord.txt.keychains.json")
+    );
+
+    keychains.save(&sidecar_path).unwrap();
+    let loaded = Keychains::load(&sidecar_path).unwrap();
+    assert_eq!(loaded.format, KEYCHAINS_FORMAT);
+    assert_eq!(loaded.fingerprint, keychains.fingerprint);
+    assert_eq!(loaded.version, keychains.version);
+    assert_eq!(loaded.department, keychains.department);
+    assert_eq!(
+        serde_json::to_string(&loaded.keychains).unwrap(),
+        serde_json::to_string(&keychains.keychains).unwrap()
+    );
+
+    // Loaded keychains decrypt the log
+    let records = log.records_with(&loaded).unwrap();
+    assert!(records.iter().any(|record| matches!(
+        record,
+        Record::OSD(osd) if (osd.latitude - 46.0).abs() < 1e-9
+    )));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn later_formats_are_rejected() {
+    let directory = temp_dir("sidecar-format");
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("log.txt.keychains.json");
+
+    let log = DJILog::from_bytes(key_storage_log(&[1, 2, 3, 4], keychains(KEY, IV))).unwrap();
+    let keychains = Keychains {
+        format: KEYCHAINS_FORMAT + 1,
+        ..log_keychains(&log)
+    };
+    keychains.save(&path).unwrap();
+
+    assert!(matches!(
+        Keychains::load(&path),
+        Err(Error::UnsupportedKeychainsFormat(format)) if format == KEYCHAINS_FORMAT + 1
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn keychains_of_another_log_are_rejected() {
+    let log = DJILog::from_bytes(key_storage_log(&[1, 2, 3, 4], keychains(KEY, IV))).unwrap();
+    let other_log = DJILog::from_bytes(key_storage_log(&[4, 3, 2, 1], keychains(KEY, IV))).unwrap();
+
+    let keychains = log_keychains(&other_log);
+    assert!(matches!(
+        log.records_with(&keychains),
+        Err(Error::KeychainMismatch { expected, found })
+            if expected == log_keychains(&log).fingerprint && found == keychains.fingerprint
+    ));
+    assert!(other_log.records_with(&keychains).is_ok());
+}
+
+#[test]
+fn truncated_logs_keep_their_fingerprint() {
+    let mut bytes = key_storage_log(&[1, 2, 3, 4], keychains(KEY, IV));
+    let log = DJILog::from_bytes(bytes.clone()).unwrap();
+    let keychains = log_keychains(&log);
+    let last_record = log
+        .records_iter(Some(Vec::new()))
+        .unwrap()
+        .envelopes()
+        .last()
+        .unwrap()
+        .offset;
+
+    // Keep the type id of the last OSD record only, as a crashed flight would
+    bytes.truncate(last_record as usize + 1);
+    let log = DJILog::from_bytes(bytes).unwrap();
+
+    let records = log.records_with(&keychains).unwrap();
+    assert_eq!(
+        records
+            .iter()
+            .filter(|record| matches!(record, Record::OSD(_)))
+            .count(),
+        1
+    );
+    assert!(log.frames_with(&keychains).is_ok());
+}
@@ -0,0 +1,216 @@
+//! Corpus of malformed logs, checking that decoding reports errors instead of panicking.
+//!
+//! Logs of each supported layout are built with `DJILogWriter`, then mutated with a
+//! deterministic pseudo-random generator: flipped bytes, truncations, inserted bytes and
+//! mismatching keychains.
+
+use std::io::Cursor;
+
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use dji_log_parser::{probe, DJILog, DJILogWriter, Error};
+
+mod common;
+
+use common::{keychains, v6_log, write_log, Rng, IV, KEY, OTHER_KEY};
+
+const MUTATIONS: usize = 500;
+
+/// Runs every decoding entry point, which must return without panicking.
+fn decode(bytes: Vec<u8>, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) {
+    let _ = probe(&bytes);
+
+    let Ok(log) = DJILog::from_bytes(bytes) else {
+        return;
+    };
+
+    let _ = log.records(keychains.clone());
+    let _ = log.frames(keychains.clone());
+    let _ = log.flights(keychains.clone());
+    if let Ok(iter) = log.records_iter(keychains) {
+        let mut iter = iter.with_recovery();
+        iter.by_ref().for_each(drop);
+        let _ = iter.into_report();
+    }
+}
+
+fn mutate(rng: &mut Rng, bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    match rng.below(4) {
+        0 => {
+            for _ in 0..=rng.below(8) {
+                let index = rng.below(bytes.len());
+                bytes[index] = rng.next() as u8;
+            }
+        }
+        1 => bytes.truncate(rng.below(bytes.len())),
+        2 => {
+            let index = rng.below(bytes.len());
+            let count = 1 + rng.below(16);
+            let inserted = rng.bytes(count);
+            bytes.splice(index..index, inserted);
+        }
+        _ => {
+            // Records section only
+            let index = 100 + rng.below(bytes.len().saturating_sub(100));
+            if index < bytes.len() {
+                bytes[index] ^= 1 << rng.below(8);
+            }
+        }
+    }
+    bytes
+}
+
+#[test]
+fn mutated_logs_do_not_panic() {
+    let logs = [
+        (write_log(6, None), None),
+        (write_log(7, None), None),
+        (write_log(12, None), None),
+        (write_log(13, None), None),
+        (
+            write_log(14, Some(keychains(KEY, IV))),
+            Some(keychains(KEY, IV)),
+        ),
+    ];
+
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    for (bytes, keychains) in logs {
+        decode(bytes.clone(), keychains.clone());
+
+        for _ in 0..MUTATIONS {
+            decode(mutate(&mut rng, &bytes), keychains.clone());
+        }
+    }
+}
+
+#[test]
+fn random_bytes_do_not_panic() {
+    let mut rng = Rng(42);
+    for length in [0, 1, 10, 99, 100, 101, 150, 600, 2000] {
+        for version in [0, 6, 7, 12, 13, 14, 255] {
+            let mut bytes = rng.bytes(length);
+            if bytes.len() > 10 {
+                bytes[10] = version;
+            }
+            decode(bytes, None);
+        }
+    }
+}
+
+#[test]
+fn mismatching_keychains_do_not_panic() {
+    let bytes = write_log(14, Some(keychains(KEY, IV)));
+
+    for (key, iv) in [
+        (OTHER_KEY, IV),
+        (KEY, "AAEC"),
+        ("AAEC", IV),
+        ("", ""),
+        ("!", "!"),
+    ] {
+        decode(bytes.clone(), Some(keychains(key, iv)));
+    }
+}
+
+#[test]
+fn decoding_errors_are_typed() {
+    let bytes = write_log(14, Some(keychains(KEY, IV)));
+    let log = DJILog::from_bytes(bytes).unwrap();
+
+    let (records, report) = log
+        .records_with_report(Some(keychains(OTHER_KEY, IV)))
+        .unwrap();
+    assert!(matches!(
+        report.failures.first().map(|failure| &failure.error),
+        Some(Error::InvalidPadding)
+    ));
+
+    // Decoding goes on past the records that cannot be decrypted
+    let expected = log.records(Some(keychains(KEY, IV))).unwrap();
+    assert_eq!(records.len(), expected.len());
+    assert!(report.failures.len() > 1);
+    let padding_errors: usize = report
+        .decryption
+        .values()
+        .map(|stats| stats.padding_errors)
+        .sum();
+    assert_eq!(padding_errors, report.failures.len());
+    assert_eq!(report.unparsed_bytes, 0);
+
+    let (_, report) = log
+        .records_with_report(Some(keychains(KEY, "AAEC")))
+        .unwrap();
+    assert!(matches!(
+        report.failures.first().map(|failure| &failure.error),
+        Some(Error::InvalidKeyLength { key: 32, iv: 3 })
+    ));
+}
+
+/// Writes a v13 log with two keychains, followed by a record of the given type.
+fn key_storage_log_ending_with(type_id: u8, payload: &[u8]) -> Vec<u8> {
+    let details = DJILog::from_bytes(v6_log()).unwrap().details;
+    let mut writer = DJILogWriter::new(Cursor::new(Vec::new()), 13, details, None).unwrap();
+    // KeyStorage records hold a feature point, a length and the encrypted keychain entry
+    let key_storage = [1, 0, 4, 0, 1, 2, 3, 4];
+    writer.write_record(1, &[7; 53]).unwrap();
+    writer.write_record(56, &key_storage).unwrap();
+    writer.write_record(50, &[7; 12]).unwrap();
+    writer.write_record(56, &key_storage).unwrap();
+    writer.write_record(type_id, payload).unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+fn last_record_offset(log: &DJILog) -> u64 {
+    log.records_iter(Some(Vec::new()))
+        .unwrap()
+        .envelopes()
+        .last()
+        .unwrap()
+        .offset
+}
+
+#[test]
+fn truncated_key_storage_is_reported() {
+    let mut bytes = key_storage_log_ending_with(56, &[1, 0, 4, 0, 5, 6, 7, 8]);
+
+    let log = DJILog::from_bytes(bytes.clone()).unwrap();
+    let request = log.keychains_request().unwrap();
+    assert_eq!(request.keychains.len(), 2);
+    assert_eq!(request.keychains[1].len(), 2);
+    let last_record = last_record_offset(&log);
+
+    // Cut the last KeyStorage record in the middle of its keychain entry
+    bytes.truncate(last_record as usize + 6);
+    let log = DJILog::from_bytes(bytes).unwrap();
+
+    assert!(matches!(
+        log.keychains_request(),
+        Err(Error::IncompleteKeyStorage { offset, .. }) if offset == last_record
+    ));
+
+    let (truncated_request, report) = log.keychains_request_with_report(None, None).unwrap();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(truncated_request.keychains.len(), 2);
+    assert_eq!(truncated_request.keychains[1].len(), 1);
+}
+
+#[test]
+fn truncated_trailing_records_keep_the_request() {
+    let mut bytes = key_storage_log_ending_with(1, &[7; 53]);
+
+    let log = DJILog::from_bytes(bytes.clone()).unwrap();
+    let expected = log.keychains_request().unwrap();
+    let last_record = last_record_offset(&log);
+
+    // Keep the type id of the trailing OSD record only, as a crashed flight would
+    bytes.truncate(last_record as usize + 1);
+    let log = DJILog::from_bytes(bytes).unwrap();
+
+    let (_, report) = log.keychains_request_with_report(None, None).unwrap();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].record_type, Some(1));
+
+    let request = log.keychains_request().unwrap();
+    assert_eq!(request.keychains.len(), 2);
+    assert_eq!(request.fingerprint(), expected.fingerprint());
+}
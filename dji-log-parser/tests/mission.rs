@@ -0,0 +1,159 @@
+//! Waypoint mission records, as laid out in `record::waypoint_mission`, `record::waypoint`
+//! and `record::gs_mission_status`.
+#![cfg(feature = "experimental-records")]
+
+use std::io::Cursor;
+
+use dji_log_parser::record::gs_mission_status::WaypointMissionState;
+use dji_log_parser::record::waypoint::{WaypointActionType, WaypointTurnMode};
+use dji_log_parser::record::waypoint_mission::{
+    WaypointMissionFinishedAction, WaypointMissionFlightPathMode,
+    WaypointMissionGotoFirstWaypointMode, WaypointMissionHeadingMode,
+};
+use dji_log_parser::record::Record;
+use dji_log_parser::{DJILog, DJILogWriter};
+
+mod common;
+
+use common::details;
+
+/// WaypointMission payload, with the point of interest at offsets 15 and 23.
+fn mission_payload(waypoint_count: u8) -> Vec<u8> {
+    let mut payload = vec![waypoint_count];
+    payload.extend(15.0f32.to_le_bytes());
+    payload.extend(8.0f32.to_le_bytes());
+    // go home, toward point of interest, curved, point to point
+    payload.extend([1, 4, 1, 1]);
+    // exit on signal lost and gimbal pitch rotation, flown twice
+    payload.extend([0x03, 2]);
+    payload.extend(46.5f64.to_le_bytes());
+    payload.extend(6.5f64.to_le_bytes());
+    assert_eq!(payload.len(), 31);
+    payload
+}
+
+/// Waypoint payload, with the coordinates at offsets 1 and 9.
+fn waypoint_payload(index: u8, latitude: f64, longitude: f64) -> Vec<u8> {
+    let mut payload = vec![index];
+    payload.extend(latitude.to_le_bytes());
+    payload.extend(longitude.to_le_bytes());
+    payload.extend(60.5f32.to_le_bytes());
+    payload.extend((-90i16).to_le_bytes());
+    payload.extend(2.5f32.to_le_bytes());
+    payload.push(1);
+    payload.extend((-45i16).to_le_bytes());
+    payload.extend(5.0f32.to_le_bytes());
+    payload.push(1);
+    payload.extend(60u16.to_le_bytes());
+    // stay 2 seconds then take a photo
+    payload.push(2);
+    payload.push(0);
+    payload.extend(2000i16.to_le_bytes());
+    payload.push(1);
+    payload.extend(0i16.to_le_bytes());
+    payload
+}
+
+fn write_log(version: u8) -> Vec<u8> {
+    let mut writer = DJILogWriter::new(Cursor::new(Vec::new()), version, details(), None).unwrap();
+    // Downloaded mission, replaced by the uploaded one
+    writer.write_record(38, &mission_payload(1)).unwrap();
+    writer
+        .write_record(39, &waypoint_payload(0, 45.0, 5.0))
+        .unwrap();
+    // Waypoints are sent out of order
+    writer.write_record(35, &mission_payload(2)).unwrap();
+    writer
+        .write_record(36, &waypoint_payload(1, 46.001, 6.001))
+        .unwrap();
+    writer
+        .write_record(36, &waypoint_payload(0, 46.0, 6.0))
+        .unwrap();
+    // Reaching waypoint 1
+    writer.write_record(32, &[1, 1, 0x01]).unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn mission_records_are_decoded() {
+    for version in [6, 7, 12] {
+        let log = DJILog::from_bytes(write_log(version)).unwrap();
+        let records = log.records(None).unwrap();
+        assert!(matches!(records[0], Record::WaypointMissionDownload(_)));
+        assert!(matches!(records[1], Record::WaypointDownload(_)));
+
+        let Record::WaypointMissionUpload(settings) = &records[2] else {
+            panic!("version {}: {:?}", version, records[2]);
+        };
+        assert_eq!(settings.waypoint_count, 2);
+        assert_eq!(
+            (settings.max_flight_speed, settings.auto_flight_speed),
+            (15.0, 8.0)
+        );
+        assert!(matches!(
+            settings.finished_action,
+            WaypointMissionFinishedAction::GoHome
+        ));
+        assert!(matches!(
+            settings.heading_mode,
+            WaypointMissionHeadingMode::TowardPointOfInterest
+        ));
+        assert!(matches!(
+            settings.flight_path_mode,
+            WaypointMissionFlightPathMode::Curved
+        ));
+        assert!(matches!(
+            settings.goto_first_waypoint_mode,
+            WaypointMissionGotoFirstWaypointMode::PointToPoint
+        ));
+        assert!(settings.exit_mission_on_rc_signal_lost);
+        assert!(settings.is_gimbal_pitch_rotation_enabled);
+        assert_eq!(settings.repeat_times, 2);
+        assert_eq!((settings.poi_latitude, settings.poi_longitude), (46.5, 6.5));
+
+        let Record::WaypointUpload(waypoint) = &records[3] else {
+            panic!("version {}: {:?}", version, records[3]);
+        };
+        assert_eq!(waypoint.index, 1);
+        assert_eq!((waypoint.latitude, waypoint.longitude), (46.001, 6.001));
+        assert_eq!((waypoint.altitude, waypoint.heading), (60.5, -90));
+        assert_eq!(waypoint.corner_radius, 2.5);
+        assert!(matches!(
+            waypoint.turn_mode,
+            WaypointTurnMode::CounterClockwise
+        ));
+        assert_eq!((waypoint.gimbal_pitch, waypoint.speed), (-45, 5.0));
+        assert_eq!(
+            (waypoint.action_repeat_times, waypoint.action_timeout),
+            (1, 60)
+        );
+        assert_eq!(waypoint.actions.len(), 2);
+        assert!(matches!(
+            waypoint.actions[0].action_type,
+            WaypointActionType::Stay
+        ));
+        assert_eq!(waypoint.actions[0].param, 2000);
+        assert!(matches!(
+            waypoint.actions[1].action_type,
+            WaypointActionType::StartTakePhoto
+        ));
+
+        let Record::GSMissionStatus(status) = &records[5] else {
+            panic!("version {}: {:?}", version, records[5]);
+        };
+        assert!(matches!(status.state, WaypointMissionState::Moving));
+        assert_eq!(status.target_waypoint_index, 1);
+        assert!(status.is_waypoint_reached);
+
+        let mission = log.mission(None).unwrap().unwrap();
+        assert!(mission.is_complete());
+        assert_eq!(
+            mission
+                .waypoints
+                .iter()
+                .map(|waypoint| waypoint.index)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+}
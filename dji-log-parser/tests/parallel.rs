@@ -0,0 +1,116 @@
+//! Parallel decoding of records with `DJILog::records_parallel`.
+#![cfg(feature = "parallel")]
+
+use dji_log_parser::keychain::{FeaturePoint, KeychainFeaturePoint};
+use dji_log_parser::{DJILog, Error};
+
+mod common;
+
+use common::{keychains, write_log, IV, KEY, OTHER_KEY};
+
+/// Keychains with another key after the `KeyStorageRecover` record.
+fn switched_keychains() -> Vec<Vec<KeychainFeaturePoint>> {
+    vec![
+        keychains(KEY, IV).remove(0),
+        keychains(OTHER_KEY, IV).remove(0),
+    ]
+}
+
+fn assert_same_records(bytes: Vec<u8>, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) {
+    let log = DJILog::from_bytes(bytes).unwrap();
+    let (records, report) = log.records_with_report(keychains.clone()).unwrap();
+    let (parallel_records, parallel_report) =
+        log.records_parallel_with_report(keychains.clone()).unwrap();
+
+    assert!(!records.is_empty());
+    assert_eq!(format!("{:?}", parallel_records), format!("{:?}", records));
+    assert_eq!(
+        format!("{:?}", log.records_parallel(keychains).unwrap()),
+        format!("{:?}", records)
+    );
+
+    assert_eq!(parallel_report.unparsed_bytes, report.unparsed_bytes);
+    assert_eq!(parallel_report.unknown_count, report.unknown_count);
+    assert_eq!(parallel_report.invalid_count, report.invalid_count);
+    assert_eq!(
+        parallel_report
+            .failures
+            .iter()
+            .map(|failure| (failure.offset, failure.record_type))
+            .collect::<Vec<_>>(),
+        report
+            .failures
+            .iter()
+            .map(|failure| (failure.offset, failure.record_type))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parallel_report.undecrypted_feature_points(),
+        report.undecrypted_feature_points()
+    );
+}
+
+#[test]
+fn parallel_records_are_the_sequential_ones() {
+    assert_same_records(write_log(6, None), None);
+    assert_same_records(write_log(12, None), None);
+    assert_same_records(
+        write_log(13, Some(keychains(KEY, IV))),
+        Some(keychains(KEY, IV)),
+    );
+}
+
+#[test]
+fn keychains_are_switched_on_key_storage_recover() {
+    let bytes = write_log(13, Some(switched_keychains()));
+    assert_same_records(bytes.clone(), Some(switched_keychains()));
+
+    // Records after the switch are only decrypted with the second keychain
+    let (_, report) = DJILog::from_bytes(bytes)
+        .unwrap()
+        .records_parallel_with_report(Some(switched_keychains()))
+        .unwrap();
+    let stats = report.decryption[&FeaturePoint::BaseFeature];
+    assert!(report.failures.is_empty());
+    assert!(stats.records > 0 && stats.is_complete());
+}
+
+#[test]
+fn decryption_failures_are_reported() {
+    // Records after the switch are decrypted with the first key
+    let bytes = write_log(13, Some(switched_keychains()));
+    assert_same_records(bytes.clone(), Some(keychains(KEY, IV)));
+
+    let (_, report) = DJILog::from_bytes(bytes)
+        .unwrap()
+        .records_parallel_with_report(Some(keychains(KEY, IV)))
+        .unwrap();
+    assert!(!report.failures.is_empty());
+    assert!(report
+        .failures
+        .iter()
+        .all(|failure| matches!(failure.error, Error::InvalidPadding)));
+}
+
+#[test]
+fn undecodable_records_are_reported() {
+    let bytes = write_log(13, Some(keychains(KEY, IV)));
+    let envelopes = DJILog::from_bytes(bytes.clone())
+        .unwrap()
+        .record_envelopes(Some(keychains(KEY, IV)))
+        .unwrap();
+    let offset = envelopes.last().unwrap().offset;
+
+    // Only the type of the last record is left
+    let bytes = bytes[..offset as usize + 1].to_vec();
+    assert_same_records(bytes.clone(), Some(keychains(KEY, IV)));
+
+    let (records, report) = DJILog::from_bytes(bytes)
+        .unwrap()
+        .records_parallel_with_report(Some(keychains(KEY, IV)))
+        .unwrap();
+    assert_eq!(records.len(), envelopes.len() - 1);
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].offset, offset);
+    assert_eq!(report.unparsed_bytes, 1);
+}
@@ -0,0 +1,86 @@
+//! Detection of DJI logs with `probe`.
+
+use dji_log_parser::{probe, DJILog, Error};
+
+mod common;
+
+use common::{keychains, write_log, IV, KEY};
+
+#[test]
+fn truncated_logs_are_probed() {
+    for (version, keychains) in [(6, None), (12, None), (13, Some(keychains(KEY, IV)))] {
+        let bytes = write_log(version, keychains.clone());
+        let records_offset = DJILog::from_bytes(bytes.clone())
+            .unwrap()
+            .records_iter(keychains)
+            .unwrap()
+            .envelopes()
+            .next()
+            .unwrap()
+            .offset as usize;
+
+        // Cuts within the first record are inconclusive, not a proof of another format
+        for length in records_offset..bytes.len() {
+            let result = probe(&bytes[..length]);
+            assert!(
+                matches!(result, Ok(ref result) if result.version == version),
+                "version {} cut at {}: {:?}",
+                version,
+                length,
+                result
+            );
+        }
+    }
+}
+
+#[test]
+fn other_files_are_rejected() {
+    let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(20);
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    png.resize(600, 0x42);
+    let json = format!(r#"{{"records":[{}]}}"#, "1,".repeat(300));
+
+    for bytes in [
+        text.as_bytes(),
+        png.as_slice(),
+        json.as_bytes(),
+        &[0u8; 600],
+        &[0u8; 20],
+    ] {
+        assert!(matches!(probe(bytes), Err(Error::NotDJILog(_))));
+    }
+}
+
+#[test]
+fn invalid_first_records_are_rejected() {
+    let mut bytes = write_log(12, None);
+    let records_offset = DJILog::from_bytes(bytes.clone())
+        .unwrap()
+        .record_envelopes(None)
+        .unwrap()[0]
+        .offset as usize;
+
+    // Missing end byte, within the probed bytes
+    let length = bytes[records_offset + 1] as usize;
+    bytes[records_offset + 2 + length] = 0;
+    assert!(matches!(probe(&bytes), Err(Error::NotDJILog(_))));
+}
+
+#[test]
+fn unknown_first_record_types_are_accepted() {
+    for (version, keychains) in [(12, None), (13, Some(keychains(KEY, IV)))] {
+        let mut bytes = write_log(version, keychains.clone());
+        let records_offset = DJILog::from_bytes(bytes.clone())
+            .unwrap()
+            .records_iter(keychains)
+            .unwrap()
+            .envelopes()
+            .next()
+            .unwrap()
+            .offset as usize;
+
+        // Record types added by newer firmwares are framed as the others
+        bytes[records_offset] = 0xEE;
+        assert!(matches!(probe(&bytes), Ok(result) if result.version == version));
+    }
+}
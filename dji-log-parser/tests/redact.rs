@@ -0,0 +1,150 @@
+//! Redaction of log files with `DJILog::redact`.
+
+use std::io::Cursor;
+
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use dji_log_parser::record::{Record, RecordEnvelope};
+use dji_log_parser::{DJILog, DJILogWriter, Error, Redaction, SerialRedaction};
+
+mod common;
+
+use common::{details, keychains, osd_payload, IV, KEY};
+
+/// Recover record payload with the given serial numbers, whose field size depends on `version`.
+fn recover_payload(version: u8, aircraft_sn: &str, camera_sn: &str) -> Vec<u8> {
+    let sn_size = if version <= 7 { 10 } else { 16 };
+    let field = |value: &str, size: usize| {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(size, 0);
+        bytes
+    };
+
+    let mut payload = vec![0, 1, 1, 2, 3];
+    payload.extend(field(aircraft_sn, sn_size));
+    payload.extend(field("Mavic", 32));
+    payload.extend(1_700_000_000i64.to_le_bytes());
+    payload.extend(field(camera_sn, sn_size));
+    payload.extend(field("", sn_size));
+    payload.extend(field("", sn_size));
+    payload
+}
+
+fn write_log(version: u8, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) -> Vec<u8> {
+    let mut writer =
+        DJILogWriter::new(Cursor::new(Vec::new()), version, details(), keychains).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 1.0))
+        .unwrap();
+    writer
+        .write_record(13, &recover_payload(version, "1ABCDE2345", "CAM0000001"))
+        .unwrap();
+    writer.write_record(5, &[7; 24]).unwrap();
+    writer.write_record(99, &[1, 2, 3, 4, 5]).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.5, 6.5, 2.0))
+        .unwrap();
+    writer.finish().unwrap().into_inner()
+}
+
+fn envelopes(
+    log: &DJILog,
+    keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+) -> Vec<RecordEnvelope> {
+    log.records_iter(keychains)
+        .unwrap()
+        .with_payloads()
+        .envelopes()
+        .collect()
+}
+
+fn assert_redacted(version: u8, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) {
+    let log = DJILog::from_bytes(write_log(version, keychains.clone())).unwrap();
+    let redaction = Redaction::new("secret")
+        .with_coordinate_shift(1.0, 2.0)
+        .with_serials(SerialRedaction::Hash);
+
+    let bytes = log
+        .redact(keychains.clone(), &redaction, Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let redacted = DJILog::from_bytes(bytes).unwrap();
+
+    let original = envelopes(&log, keychains.clone());
+    let copy = envelopes(&redacted, keychains);
+    assert_eq!(copy.len(), original.len());
+    assert!(matches!(original[1].record, Record::Recover(_)));
+
+    let sn_size = if version <= 7 { 10 } else { 16 };
+    for (copy, original) in copy.iter().zip(&original) {
+        assert_eq!(copy.type_id, original.type_id);
+        match (&copy.record, &original.record) {
+            (Record::OSD(copy), Record::OSD(original)) => {
+                assert!((copy.latitude - original.latitude - 1.0).abs() < 1e-9);
+                assert!((copy.longitude - original.longitude - 2.0).abs() < 1e-9);
+            }
+            (Record::Recover(copy), Record::Recover(original)) => {
+                // Hashes are derived at the field width, not truncated
+                let hash = redaction.redact_serial(&original.aircraft_sn);
+                assert_eq!(copy.aircraft_sn, hash[16 - sn_size..]);
+                assert_ne!(copy.camera_sn, original.camera_sn);
+                assert_eq!(copy.camera_sn.len(), sn_size);
+                assert_eq!(copy.aircraft_name, original.aircraft_name);
+            }
+            _ => assert_eq!(copy.payload, original.payload),
+        }
+    }
+}
+
+#[test]
+fn v7_logs_are_redacted() {
+    assert_redacted(7, None);
+}
+
+#[test]
+fn v13_logs_are_redacted() {
+    assert_redacted(13, Some(keychains(KEY, IV)));
+}
+
+#[test]
+fn experimental_records_are_blanked() {
+    let type_ids = [26, 27, 35, 36, 38, 39];
+
+    let mut writer = DJILogWriter::new(Cursor::new(Vec::new()), 7, details(), None).unwrap();
+    writer
+        .write_record(1, &osd_payload(46.0, 6.0, 1.0))
+        .unwrap();
+    for type_id in type_ids {
+        writer.write_record(type_id, &[7; 40]).unwrap();
+    }
+    let log = DJILog::from_bytes(writer.finish().unwrap().into_inner()).unwrap();
+
+    let bytes = log
+        .redact(None, &Redaction::new("secret"), Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let redacted = DJILog::from_bytes(bytes).unwrap();
+
+    let copy = envelopes(&redacted, None);
+    assert_eq!(copy.len(), type_ids.len() + 1);
+    for (envelope, type_id) in copy[1..].iter().zip(type_ids) {
+        assert_eq!(envelope.type_id, type_id);
+        assert_eq!(envelope.payload, Some(vec![0; 40]));
+    }
+}
+
+#[test]
+fn undecodable_records_are_errors() {
+    let keychains = Some(keychains(KEY, IV));
+    let mut bytes = write_log(13, keychains.clone());
+    let log = DJILog::from_bytes(bytes.clone()).unwrap();
+    let last_record = envelopes(&log, keychains.clone()).last().unwrap().offset;
+
+    // Keep the type id of the last record only, which cannot be decoded
+    bytes.truncate(last_record as usize + 1);
+    let log = DJILog::from_bytes(bytes).unwrap();
+
+    assert!(matches!(
+        log.redact(keychains, &Redaction::new("secret"), Cursor::new(Vec::new())),
+        Err(Error::IncompleteRecords { offset, .. }) if offset == last_record
+    ));
+}
@@ -0,0 +1,83 @@
+//! Round trips of records through `DJILogWriter`.
+
+use std::io::Cursor;
+
+use dji_log_parser::keychain::KeychainFeaturePoint;
+use dji_log_parser::record::RecordEnvelope;
+use dji_log_parser::{DJILog, DJILogWriter, Error};
+
+mod common;
+
+use common::{keychains, write_log, IV, KEY};
+
+fn envelopes(
+    log: &DJILog,
+    keychains: Option<Vec<Vec<KeychainFeaturePoint>>>,
+) -> Vec<RecordEnvelope> {
+    log.records_iter(keychains)
+        .unwrap()
+        .with_payloads()
+        .envelopes()
+        .collect()
+}
+
+fn assert_round_trip(version: u8, keychains: Option<Vec<Vec<KeychainFeaturePoint>>>) {
+    let log = DJILog::from_bytes(write_log(version, keychains.clone())).unwrap();
+    let records = envelopes(&log, keychains.clone());
+    assert!(records.iter().all(|envelope| envelope.payload.is_some()));
+
+    let mut writer = DJILogWriter::new(
+        Cursor::new(Vec::new()),
+        version,
+        log.details.clone(),
+        keychains.clone(),
+    )
+    .unwrap();
+    for envelope in &records {
+        writer.write_envelope(envelope).unwrap();
+    }
+    let bytes = writer.finish().unwrap().into_inner();
+
+    let copy = DJILog::from_bytes(bytes).unwrap();
+    assert_eq!(copy.version, version);
+    assert_eq!(copy.details.aircraft_sn, log.details.aircraft_sn);
+
+    let copied_records = envelopes(&copy, keychains);
+    assert_eq!(copied_records.len(), records.len());
+    for (copied, original) in copied_records.iter().zip(&records) {
+        assert_eq!(copied.type_id, original.type_id);
+        assert_eq!(copied.payload, original.payload);
+        assert_eq!(
+            format!("{:?}", copied.record),
+            format!("{:?}", original.record)
+        );
+    }
+}
+
+#[test]
+fn v6_records_round_trip() {
+    assert_round_trip(6, None);
+}
+
+#[test]
+fn v12_records_round_trip() {
+    assert_round_trip(12, None);
+}
+
+#[test]
+fn v13_records_round_trip() {
+    assert_round_trip(13, Some(keychains(KEY, IV)));
+}
+
+#[test]
+fn envelopes_without_payload_are_rejected() {
+    let log = DJILog::from_bytes(write_log(12, None)).unwrap();
+    let envelope = log.record_envelopes(None).unwrap().remove(0);
+
+    let mut writer =
+        DJILogWriter::new(Cursor::new(Vec::new()), 12, log.details.clone(), None).unwrap();
+    assert!(matches!(
+        writer.write_envelope(&envelope),
+        Err(Error::MissingPayload(offset)) if offset == envelope.offset
+    ));
+}
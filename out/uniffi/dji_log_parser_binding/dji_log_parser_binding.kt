@@ -723,6 +723,10 @@ internal interface UniffiForeignFutureCompleteVoid : com.sun.jna.Callback {
 
 
 
+
+
+
+
 
 
 
@@ -753,10 +757,14 @@ fun uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_keychains_reques
 ): Short
 fun uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_records(
 ): Short
+fun uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_records_and_frames(
+): Short
 fun uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_version(
 ): Short
 fun uniffi_dji_log_parser_binding_checksum_constructor_djilogwrapper_from_bytes(
 ): Short
+fun uniffi_dji_log_parser_binding_checksum_constructor_djilogwrapper_open(
+): Short
 fun ffi_dji_log_parser_binding_uniffi_contract_version(
 ): Int
 
@@ -812,6 +820,8 @@ fun uniffi_dji_log_parser_binding_fn_free_djilogwrapper(`ptr`: Pointer,uniffi_ou
 ): Unit
 fun uniffi_dji_log_parser_binding_fn_constructor_djilogwrapper_from_bytes(`bytes`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
 ): Pointer
+fun uniffi_dji_log_parser_binding_fn_constructor_djilogwrapper_open(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Pointer
 fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_details(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
 ): RustBuffer.ByValue
 fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_fetch_keychains(`ptr`: Pointer,`apiKey`: RustBuffer.ByValue,`departmentWrapper`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
@@ -822,6 +832,8 @@ fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_keychains_request(`ptr
 ): RustBuffer.ByValue
 fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_records(`ptr`: Pointer,`keychains`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
 ): RustBuffer.ByValue
+fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_records_and_frames(`ptr`: Pointer,`keychains`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
 fun uniffi_dji_log_parser_binding_fn_method_djilogwrapper_version(`ptr`: Pointer,uniffi_out_err: UniffiRustCallStatus, 
 ): Byte
 fun ffi_dji_log_parser_binding_rustbuffer_alloc(`size`: Long,uniffi_out_err: UniffiRustCallStatus, 
@@ -965,12 +977,18 @@ private fun uniffiCheckApiChecksums(lib: IntegrityCheckingUniffiLib) {
     if (lib.uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_records() != 47140.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_records_and_frames() != 11817.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
     if (lib.uniffi_dji_log_parser_binding_checksum_method_djilogwrapper_version() != 42293.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
     if (lib.uniffi_dji_log_parser_binding_checksum_constructor_djilogwrapper_from_bytes() != 50341.toShort()) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
+    if (lib.uniffi_dji_log_parser_binding_checksum_constructor_djilogwrapper_open() != 15542.toShort()) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
 }
 
 /**
@@ -1503,6 +1521,11 @@ public interface DjiLogWrapperInterface {
      */
     fun `records`(`keychains`: List<List<KeychainFeaturePointWrapper>>?): List<RecordWrapper>
     
+    /**
+     * Retrieves both the raw records and the normalized frames, decoding the log only once
+     */
+    fun `recordsAndFrames`(`keychains`: List<List<KeychainFeaturePointWrapper>>?): RecordsAndFramesWrapper
+    
     /**
      * Get the log format version
      */
@@ -1676,6 +1699,22 @@ open class DjiLogWrapper: Disposable, AutoCloseable, DjiLogWrapperInterface
     
 
     
+    /**
+     * Retrieves both the raw records and the normalized frames, decoding the log only once
+     */
+    @Throws(DjiException::class)override fun `recordsAndFrames`(`keychains`: List<List<KeychainFeaturePointWrapper>>?): RecordsAndFramesWrapper {
+            return FfiConverterTypeRecordsAndFramesWrapper.lift(
+    callWithPointer {
+    uniffiRustCallWithError(DjiException) { _status ->
+    UniffiLib.INSTANCE.uniffi_dji_log_parser_binding_fn_method_djilogwrapper_records_and_frames(
+        it, FfiConverterOptionalSequenceSequenceTypeKeychainFeaturePointWrapper.lower(`keychains`),_status)
+}
+    }
+    )
+    }
+    
+
+    
     /**
      * Get the log format version
      */override fun `version`(): kotlin.UByte {
@@ -1712,6 +1751,23 @@ open class DjiLogWrapper: Disposable, AutoCloseable, DjiLogWrapperInterface
     
 
         
+    /**
+     * Constructs a `DJILog` from a file path.
+     *
+     * Records are read from the file on demand, so the log is never copied
+     * across the FFI boundary nor fully loaded in memory.
+     */
+    @Throws(DjiException::class) fun `open`(`path`: kotlin.String): DjiLogWrapper {
+            return FfiConverterTypeDJILogWrapper.lift(
+    uniffiRustCallWithError(DjiException) { _status ->
+    UniffiLib.INSTANCE.uniffi_dji_log_parser_binding_fn_constructor_djilogwrapper_open(
+        FfiConverterString.lower(`path`),_status)
+}
+    )
+    }
+    
+
+        
     }
     
 }
@@ -2150,6 +2206,38 @@ public object FfiConverterTypeRecordWrapper: FfiConverterRustBuffer<RecordWrappe
 
 
 
+data class RecordsAndFramesWrapper (
+    var `records`: List<RecordWrapper>, 
+    var `frames`: List<FrameWrapper>
+) {
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRecordsAndFramesWrapper: FfiConverterRustBuffer<RecordsAndFramesWrapper> {
+    override fun read(buf: ByteBuffer): RecordsAndFramesWrapper {
+        return RecordsAndFramesWrapper(
+            FfiConverterSequenceTypeRecordWrapper.read(buf),
+            FfiConverterSequenceTypeFrameWrapper.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RecordsAndFramesWrapper) = (
+            FfiConverterSequenceTypeRecordWrapper.allocationSize(value.`records`) +
+            FfiConverterSequenceTypeFrameWrapper.allocationSize(value.`frames`)
+    )
+
+    override fun write(value: RecordsAndFramesWrapper, buf: ByteBuffer) {
+            FfiConverterSequenceTypeRecordWrapper.write(value.`records`, buf)
+            FfiConverterSequenceTypeFrameWrapper.write(value.`frames`, buf)
+    }
+}
+
+
+
 
 
 sealed class DjiException: kotlin.Exception() {